@@ -0,0 +1,1132 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use itertools::Itertools;
+
+use self::output::{EpicRecord, Format, StoryRecord, UserRecord};
+use crate::{
+    config::{self, Config, ProjectConfig, Selection},
+    db::{self, Database, JiraDatabase},
+    io_utils,
+    models::{
+        parse_due_date, DBState, Epic, ItemKey, KeyPrefix, Priority, Status, Story, StoryRank, User,
+    },
+    undo::UndoHistory,
+};
+
+mod output;
+
+/// Without a subcommand the interactive board is started.
+#[derive(Parser)]
+#[command(version, about = "Manage epics and stories from the terminal")]
+pub struct Cli {
+    /// Database file to use instead of the configured projects (.json, .sqlite or .log)
+    #[arg(long, global = true, env = "JIRA_DB")]
+    pub db: Option<PathBuf>,
+    /// Project from the config file to open
+    #[arg(long, short, global = true)]
+    pub project: Option<String>,
+    /// Run the board line by line instead of full-screen, as when not in a terminal
+    #[arg(long)]
+    pub plain: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Create, list and update epics
+    #[command(subcommand)]
+    Epic(EpicCommand),
+    /// Create, list and update stories
+    #[command(subcommand)]
+    Story(StoryCommand),
+    /// Manage the people items can be assigned to
+    #[command(subcommand)]
+    User(UserCommand),
+    /// Import a json database into a new SQLite database
+    MigrateToSqlite {
+        /// Defaults to the json file with a .sqlite extension
+        #[arg(long)]
+        to: Option<PathBuf>,
+    },
+    /// Start an event log from a json database
+    MigrateToEventLog {
+        /// Defaults to the json file with a .log extension
+        #[arg(long)]
+        to: Option<PathBuf>,
+    },
+    /// Print who changed what, and when, from the event log
+    Log,
+    /// Print the board as it was at a point in time, from the event log
+    BoardAt {
+        /// A date (2023-05-19, meaning the end of that day in UTC) or an RFC 3339 timestamp
+        at: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EpicCommand {
+    /// Create an epic and print its key
+    Create(NewItem),
+    /// List all epics with their stories
+    List {
+        /// Only the epics carrying this label
+        #[arg(long)]
+        label: Option<String>,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+        #[arg(long, value_enum, default_value_t)]
+        sort: SortBy,
+    },
+    /// Show an epic and its stories
+    Show {
+        epic_id: ItemKey,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Change the name of an epic
+    Rename { epic_id: ItemKey, name: String },
+    /// Change the description of an epic, an empty one clears it
+    Describe {
+        epic_id: ItemKey,
+        #[command(flatten)]
+        description: NewDescription,
+    },
+    /// Change the status of an epic (open, in-progress, resolved, closed)
+    Status { epic_id: ItemKey, status: Status },
+    /// Change the priority of an epic (highest, high, medium, low, lowest)
+    Priority {
+        epic_id: ItemKey,
+        priority: Priority,
+    },
+    /// Make one of the board's users the owner of an epic
+    Owner { epic_id: ItemKey, username: String },
+    /// Leave an epic without owner
+    RemoveOwner { epic_id: ItemKey },
+    /// Add a label to an epic
+    Label { epic_id: ItemKey, label: String },
+    /// Remove a label from an epic
+    Unlabel { epic_id: ItemKey, label: String },
+    /// Set the due date of an epic (2023-05-19, +3d, +2w, next friday)
+    Due {
+        epic_id: ItemKey,
+        #[arg(value_parser = due_date)]
+        date: NaiveDate,
+    },
+    /// Remove the due date of an epic
+    RemoveDue { epic_id: ItemKey },
+    /// Move an epic and its stories to the trash
+    Delete { epic_id: ItemKey },
+}
+
+#[derive(Subcommand)]
+pub enum StoryCommand {
+    /// Create a story in an epic and print its key
+    Create {
+        #[arg(long)]
+        epic: ItemKey,
+        #[command(flatten)]
+        story: NewItem,
+    },
+    /// List all stories, or only those of one epic
+    List {
+        #[arg(long)]
+        epic: Option<ItemKey>,
+        /// Only the stories assigned to this username
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Only the stories carrying this label
+        #[arg(long)]
+        label: Option<String>,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+        #[arg(long, value_enum, default_value_t)]
+        sort: SortBy,
+    },
+    /// Show a story
+    Show {
+        story_id: ItemKey,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Change the name of a story
+    Rename { story_id: ItemKey, name: String },
+    /// Change the description of a story, an empty one clears it
+    Describe {
+        story_id: ItemKey,
+        #[command(flatten)]
+        description: NewDescription,
+    },
+    /// Change the status of a story (open, in-progress, resolved, closed)
+    Status { story_id: ItemKey, status: Status },
+    /// Change the priority of a story (highest, high, medium, low, lowest)
+    Priority {
+        story_id: ItemKey,
+        priority: Priority,
+    },
+    /// Assign a story to one of the board's users
+    Assign { story_id: ItemKey, username: String },
+    /// Leave a story unassigned
+    Unassign { story_id: ItemKey },
+    /// Add a label to a story
+    Label { story_id: ItemKey, label: String },
+    /// Remove a label from a story
+    Unlabel { story_id: ItemKey, label: String },
+    /// Set the due date of a story (2023-05-19, +3d, +2w, next friday)
+    Due {
+        story_id: ItemKey,
+        #[arg(value_parser = due_date)]
+        date: NaiveDate,
+    },
+    /// Remove the due date of a story
+    RemoveDue { story_id: ItemKey },
+    /// Comment on a story and print the comment's number
+    Comment { story_id: ItemKey, body: String },
+    /// Change the text of one of your comments
+    EditComment {
+        story_id: ItemKey,
+        comment_id: u32,
+        body: String,
+    },
+    /// Delete one of your comments
+    DeleteComment { story_id: ItemKey, comment_id: u32 },
+    /// Move a story to another epic, at the end of its stories
+    Move {
+        story_id: ItemKey,
+        #[arg(long)]
+        epic: ItemKey,
+    },
+    /// Change the place of a story in its epic's ranking
+    Rank {
+        story_id: ItemKey,
+        #[command(flatten)]
+        rank: NewRank,
+    },
+    /// Move a story to the trash
+    Delete { story_id: ItemKey },
+}
+
+#[derive(Subcommand)]
+pub enum UserCommand {
+    /// Add someone to the board, or change their name
+    Add {
+        username: String,
+        /// Full name
+        #[arg(long, short, default_value = "")]
+        name: String,
+    },
+    /// List the board's users
+    List {
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Remove someone from the board, unassigning their epics and stories
+    Remove { username: String },
+}
+
+#[derive(Args)]
+pub struct NewItem {
+    name: String,
+    #[arg(long, short, default_value = "")]
+    description: String,
+    #[arg(long, default_value_t)]
+    priority: Priority,
+    /// 2023-05-19, +3d, +2w or next friday
+    #[arg(long, value_parser = due_date)]
+    due: Option<NaiveDate>,
+}
+
+impl NewItem {
+    fn epic(self) -> Epic {
+        let mut epic = Epic::new(self.name, self.description);
+        epic.priority = self.priority;
+        epic.due_date = self.due;
+        epic
+    }
+
+    fn story(self) -> Story {
+        let mut story = Story::new(self.name, self.description);
+        story.priority = self.priority;
+        story.due_date = self.due;
+        story
+    }
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct NewDescription {
+    text: Option<String>,
+    /// Write it in $VISUAL or $EDITOR, starting from the current one
+    #[arg(long, short)]
+    edit: bool,
+}
+
+impl NewDescription {
+    fn resolve(self, current: &str) -> Result<String> {
+        match self.text {
+            Some(text) => Ok(text),
+            None => io_utils::edit_in_editor(io_utils::DESCRIPTION_HEADER, current),
+        }
+    }
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct NewRank {
+    /// Right before another story of the epic
+    #[arg(long)]
+    before: Option<ItemKey>,
+    /// First of the epic
+    #[arg(long)]
+    top: bool,
+    /// One place up
+    #[arg(long)]
+    up: bool,
+    /// One place down
+    #[arg(long)]
+    down: bool,
+}
+
+impl NewRank {
+    fn resolve(self, keys: &KeyPrefix) -> Result<StoryRank> {
+        match self.before {
+            Some(before) => Ok(StoryRank::Before(keys.resolve(&before)?)),
+            None if self.top => Ok(StoryRank::Top),
+            None if self.up => Ok(StoryRank::Up),
+            None => Ok(StoryRank::Down),
+        }
+    }
+}
+
+/// Due dates are relative to the local date.
+fn due_date(input: &str) -> Result<NaiveDate> {
+    parse_due_date(input, io_utils::today())
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    /// By id, stories of an epic in the epic's order
+    #[default]
+    Id,
+    /// Highest priority first, then as for id
+    Priority,
+}
+
+pub fn run(command: Command, config: &Config, selection: Selection) -> Result<()> {
+    let project = selection.database()?;
+    match command {
+        Command::Epic(command) => {
+            with_database(config, &project, |db| run_epic_command(db, command))
+        }
+        Command::Story(command) => {
+            with_database(config, &project, |db| run_story_command(db, command))
+        }
+        Command::User(command) => {
+            with_database(config, &project, |db| run_user_command(db, command))
+        }
+        Command::MigrateToSqlite { to } => migrate_to_sqlite(&project.db, to),
+        Command::MigrateToEventLog { to } => migrate_to_event_log(config, &project.db, to),
+        Command::Log => print_event_log(&project.db),
+        Command::BoardAt { at } => print_board_at(&project.db, &at),
+    }
+}
+
+/// Opens the database the interactive board would use, and records the change a command
+/// made in the same undo history, so it can be undone from the board.
+fn with_database(
+    config: &Config,
+    project: &ProjectConfig,
+    run: impl FnOnce(&JiraDatabase) -> Result<Option<&'static str>>,
+) -> Result<()> {
+    let db = config.open_database(project)?;
+    let label = run(&db)?;
+
+    if let (Some(label), Some((before, after))) = (label, db.take_last_write()) {
+        let mut undo_history = UndoHistory::load(config::undo_history_path(&project.db))?;
+        undo_history.record(label.to_owned(), before, after)?;
+    }
+    Ok(())
+}
+
+/// Returns the undo label of the change made, if any.
+fn run_epic_command(db: &JiraDatabase, command: EpicCommand) -> Result<Option<&'static str>> {
+    let keys = db.key_prefix();
+    match command {
+        EpicCommand::Create(epic) => {
+            let epic_id = db.create_epic(epic.epic())?;
+            println!("{}", keys.key(epic_id));
+            Ok(Some("CreateEpic"))
+        }
+        EpicCommand::List {
+            label,
+            format,
+            sort,
+        } => {
+            let db_state = db.read()?;
+            let records = db_state
+                .epics
+                .iter()
+                .filter(|(_, epic)| {
+                    label
+                        .as_ref()
+                        .is_none_or(|label| epic.labels.contains(label))
+                })
+                .sorted_by_key(|(id, epic)| {
+                    ((sort == SortBy::Priority).then_some(epic.priority), **id)
+                })
+                .map(|(epic_id, _)| EpicRecord::new(&db_state, keys, *epic_id))
+                .collect::<Result<Vec<_>>>()?;
+            output::print_epics(&records, format)?;
+            Ok(None)
+        }
+        EpicCommand::Show { epic_id, format } => {
+            let epic_id = keys.resolve(&epic_id)?;
+            let record = EpicRecord::new(&db.read()?, keys, epic_id)?;
+            output::print_epic(&record, format)?;
+            Ok(None)
+        }
+        EpicCommand::Rename { epic_id, name } => {
+            db.update_epic_name(keys.resolve(&epic_id)?, &name)?;
+            Ok(Some("UpdateEpicName"))
+        }
+        EpicCommand::Describe {
+            epic_id,
+            description,
+        } => {
+            let epic_id = keys.resolve(&epic_id)?;
+            let current = db
+                .read()?
+                .epics
+                .remove(&epic_id)
+                .map(|epic| epic.description);
+            let description = description.resolve(current.as_deref().unwrap_or(""))?;
+            db.update_epic_description(epic_id, &description)?;
+            Ok(Some("UpdateEpicDescription"))
+        }
+        EpicCommand::Status { epic_id, status } => {
+            db.update_epic_status(keys.resolve(&epic_id)?, status)?;
+            Ok(Some("UpdateEpicStatus"))
+        }
+        EpicCommand::Priority { epic_id, priority } => {
+            db.update_epic_priority(keys.resolve(&epic_id)?, priority)?;
+            Ok(Some("UpdateEpicPriority"))
+        }
+        EpicCommand::Owner { epic_id, username } => {
+            db.update_epic_owner(keys.resolve(&epic_id)?, Some(username))?;
+            Ok(Some("UpdateEpicOwner"))
+        }
+        EpicCommand::RemoveOwner { epic_id } => {
+            db.update_epic_owner(keys.resolve(&epic_id)?, None)?;
+            Ok(Some("RemoveEpicOwner"))
+        }
+        EpicCommand::Label { epic_id, label } => {
+            db.add_epic_label(keys.resolve(&epic_id)?, &label)?;
+            Ok(Some("AddEpicLabel"))
+        }
+        EpicCommand::Unlabel { epic_id, label } => {
+            db.remove_epic_label(keys.resolve(&epic_id)?, &label)?;
+            Ok(Some("RemoveEpicLabel"))
+        }
+        EpicCommand::Due { epic_id, date } => {
+            db.update_epic_due_date(keys.resolve(&epic_id)?, Some(date))?;
+            Ok(Some("UpdateEpicDueDate"))
+        }
+        EpicCommand::RemoveDue { epic_id } => {
+            db.update_epic_due_date(keys.resolve(&epic_id)?, None)?;
+            Ok(Some("RemoveEpicDueDate"))
+        }
+        EpicCommand::Delete { epic_id } => {
+            db.delete_epic(keys.resolve(&epic_id)?)?;
+            Ok(Some("DeleteEpic"))
+        }
+    }
+}
+
+fn run_story_command(db: &JiraDatabase, command: StoryCommand) -> Result<Option<&'static str>> {
+    let keys = db.key_prefix();
+    match command {
+        StoryCommand::Create { epic, story } => {
+            let epic_id = keys.resolve(&epic)?;
+            let story_id = db.create_story(story.story(), epic_id)?;
+            println!("{}", keys.key(story_id));
+            Ok(Some("CreateStory"))
+        }
+        StoryCommand::List {
+            epic,
+            assignee,
+            label,
+            format,
+            sort,
+        } => {
+            let db_state = db.read()?;
+            let mut records = match epic {
+                Some(epic_id) => EpicRecord::new(&db_state, keys, keys.resolve(&epic_id)?)?.stories,
+                None => {
+                    let story_epics = output::story_epics(&db_state);
+                    db_state
+                        .stories
+                        .keys()
+                        .sorted()
+                        .map(|id| {
+                            StoryRecord::new(&db_state, keys, *id, story_epics.get(id).copied())
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                }
+            };
+            if let Some(assignee) = assignee {
+                records.retain(|record| record.assignee.as_ref() == Some(&assignee));
+            }
+            if let Some(label) = label {
+                records.retain(|record| record.labels.contains(&label));
+            }
+            if sort == SortBy::Priority {
+                // Stable, so stories of the same priority keep their order.
+                records.sort_by_key(|record| db_state.stories[&record.id].priority);
+            }
+            output::print_stories(&records, format)?;
+            Ok(None)
+        }
+        StoryCommand::Show { story_id, format } => {
+            let story_id = keys.resolve(&story_id)?;
+            let db_state = db.read()?;
+            let epic_id = output::story_epics(&db_state).get(&story_id).copied();
+            let record = StoryRecord::new(&db_state, keys, story_id, epic_id)?;
+            output::print_story(&record, format)?;
+            Ok(None)
+        }
+        StoryCommand::Rename { story_id, name } => {
+            db.update_story_name(keys.resolve(&story_id)?, &name)?;
+            Ok(Some("UpdateStoryName"))
+        }
+        StoryCommand::Describe {
+            story_id,
+            description,
+        } => {
+            let story_id = keys.resolve(&story_id)?;
+            let current = db
+                .read()?
+                .stories
+                .remove(&story_id)
+                .map(|story| story.description);
+            let description = description.resolve(current.as_deref().unwrap_or(""))?;
+            db.update_story_description(story_id, &description)?;
+            Ok(Some("UpdateStoryDescription"))
+        }
+        StoryCommand::Status { story_id, status } => {
+            db.update_story_status(keys.resolve(&story_id)?, status)?;
+            Ok(Some("UpdateStoryStatus"))
+        }
+        StoryCommand::Priority { story_id, priority } => {
+            db.update_story_priority(keys.resolve(&story_id)?, priority)?;
+            Ok(Some("UpdateStoryPriority"))
+        }
+        StoryCommand::Assign { story_id, username } => {
+            db.assign_story(keys.resolve(&story_id)?, Some(username))?;
+            Ok(Some("AssignStory"))
+        }
+        StoryCommand::Unassign { story_id } => {
+            db.assign_story(keys.resolve(&story_id)?, None)?;
+            Ok(Some("UnassignStory"))
+        }
+        StoryCommand::Label { story_id, label } => {
+            db.add_story_label(keys.resolve(&story_id)?, &label)?;
+            Ok(Some("AddStoryLabel"))
+        }
+        StoryCommand::Unlabel { story_id, label } => {
+            db.remove_story_label(keys.resolve(&story_id)?, &label)?;
+            Ok(Some("RemoveStoryLabel"))
+        }
+        StoryCommand::Due { story_id, date } => {
+            db.update_story_due_date(keys.resolve(&story_id)?, Some(date))?;
+            Ok(Some("UpdateStoryDueDate"))
+        }
+        StoryCommand::RemoveDue { story_id } => {
+            db.update_story_due_date(keys.resolve(&story_id)?, None)?;
+            Ok(Some("RemoveStoryDueDate"))
+        }
+        StoryCommand::Comment { story_id, body } => {
+            let comment_id = db.add_comment(keys.resolve(&story_id)?, &body)?;
+            println!("#{comment_id}");
+            Ok(Some("CommentOnStory"))
+        }
+        StoryCommand::EditComment {
+            story_id,
+            comment_id,
+            body,
+        } => {
+            db.edit_comment(keys.resolve(&story_id)?, comment_id, &body)?;
+            Ok(Some("EditComment"))
+        }
+        StoryCommand::DeleteComment {
+            story_id,
+            comment_id,
+        } => {
+            db.delete_comment(keys.resolve(&story_id)?, comment_id)?;
+            Ok(Some("DeleteComment"))
+        }
+        StoryCommand::Move { story_id, epic } => {
+            db.move_story(keys.resolve(&story_id)?, keys.resolve(&epic)?)?;
+            Ok(Some("MoveStory"))
+        }
+        StoryCommand::Rank { story_id, rank } => {
+            let rank = rank.resolve(keys)?;
+            db.rank_story(keys.resolve(&story_id)?, rank)?;
+            Ok(Some(match rank {
+                StoryRank::Up => "MoveStoryUp",
+                StoryRank::Down => "MoveStoryDown",
+                StoryRank::Top => "MoveStoryToTop",
+                StoryRank::Before(_) => "RankStoryBefore",
+            }))
+        }
+        StoryCommand::Delete { story_id } => {
+            let story_id = keys.resolve(&story_id)?;
+            let epic_id = output::story_epics(&db.read()?)
+                .get(&story_id)
+                .copied()
+                .ok_or_else(|| anyhow!("Story {} not found", keys.key(story_id)))?;
+            db.delete_story(epic_id, story_id)?;
+            Ok(Some("DeleteStory"))
+        }
+    }
+}
+
+fn run_user_command(db: &JiraDatabase, command: UserCommand) -> Result<Option<&'static str>> {
+    match command {
+        UserCommand::Add { username, name } => {
+            db.add_user(&username, User { name })?;
+            Ok(Some("AddUser"))
+        }
+        UserCommand::List { format } => {
+            let records = db
+                .read()?
+                .users
+                .iter()
+                .map(|(username, user)| UserRecord::new(username, user))
+                .collect::<Vec<_>>();
+            output::print_users(&records, format)?;
+            Ok(None)
+        }
+        UserCommand::Remove { username } => {
+            db.remove_user(&username)?;
+            Ok(Some("RemoveUser"))
+        }
+    }
+}
+
+fn migrate_to_sqlite(source: &Path, to: Option<PathBuf>) -> Result<()> {
+    let target = migration_target(source, to, "sqlite")?;
+    let db_state = db::SqliteDatabase::open(&target.to_string_lossy())
+        .and_then(|sqlite_db| sqlite_db.import_json(&source.to_string_lossy()))
+        .context("Error migrating database")?;
+
+    print_migration(&db_state, source, &target);
+    Ok(())
+}
+
+fn migrate_to_event_log(config: &Config, source: &Path, to: Option<PathBuf>) -> Result<()> {
+    let target = migration_target(source, to, "log")?;
+    let event_log = db::EventLogDatabase::new(target.to_string_lossy().into_owned());
+    if let Some(user) = &config.user {
+        event_log.set_author(user);
+    }
+    let db_state = event_log
+        .import_json(&source.to_string_lossy())
+        .context("Error migrating database")?;
+
+    print_migration(&db_state, source, &target);
+    Ok(())
+}
+
+/// Migrations read a json database. Without `--to` the new one is written next to it,
+/// keeping the file names `data/` always used.
+fn migration_target(source: &Path, to: Option<PathBuf>, extension: &str) -> Result<PathBuf> {
+    if source.extension().and_then(|extension| extension.to_str()) != Some("json") {
+        return Err(anyhow!(
+            "Only json databases can be migrated, {} isn't one",
+            source.display()
+        ));
+    }
+    Ok(to.unwrap_or_else(|| match extension {
+        "log" if source == Path::new(config::JSON_DB_PATH) => config::EVENT_LOG_PATH.into(),
+        _ => source.with_extension(extension),
+    }))
+}
+
+fn print_migration(db_state: &DBState, source: &Path, target: &Path) {
+    println!(
+        "Imported {} epics and {} stories from {} into {}",
+        db_state.epics.len(),
+        db_state.stories.len(),
+        source.display(),
+        target.display()
+    );
+}
+
+fn event_log(db_path: &Path) -> Result<db::EventLogDatabase> {
+    if db_path.extension().and_then(|extension| extension.to_str()) != Some("log") {
+        return Err(anyhow!(
+            "{} is not an event log, start one with migrate-to-event-log",
+            db_path.display()
+        ));
+    }
+    Ok(db::EventLogDatabase::new(
+        db_path.to_string_lossy().into_owned(),
+    ))
+}
+
+fn print_event_log(db_path: &Path) -> Result<()> {
+    let entries = event_log(db_path)?
+        .entries()
+        .context("Error reading event log")?;
+
+    for entry in entries {
+        for event in entry.events {
+            println!("{} | {} | {}", entry.at.to_rfc3339(), entry.actor, event);
+        }
+    }
+    Ok(())
+}
+
+fn print_board_at(db_path: &Path, at: &str) -> Result<()> {
+    let event_log = event_log(db_path)?;
+    let db_state = parse_point_in_time(at)
+        .and_then(|at| event_log.read_at(at))
+        .context("Error reading event log")?;
+
+    for (epic_id, epic) in db_state.epics.iter().sorted_by_key(|(id, _)| **id) {
+        println!("{epic_id} | {} | {}", epic.name, epic.status);
+        for story_id in &epic.stories {
+            if let Some(story) = db_state.stories.get(story_id) {
+                println!("    {story_id} | {} | {}", story.name, story.status);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Accepts an RFC 3339 timestamp or a date, which means the end of that day (UTC).
+fn parse_point_in_time(input: &str) -> Result<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(input) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(23, 59, 59))
+        .map(|at| at.and_utc())
+        .ok_or_else(|| anyhow!("Expected a date (2023-05-19) or an RFC 3339 timestamp"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+
+    fn build_db() -> JiraDatabase {
+        let mut db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        db.set_key_prefix(KeyPrefix::new("PAY").unwrap());
+        db
+    }
+
+    fn key(input: &str) -> ItemKey {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn cli_should_parse_subcommands() {
+        let cli = Cli::try_parse_from(["jira", "story", "status", "7", "in-progress"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Story(StoryCommand::Status {
+                story_id: ItemKey {
+                    prefix: None,
+                    id: 7
+                },
+                status: Status::InProgress
+            }))
+        ));
+
+        let cli =
+            Cli::try_parse_from(["jira", "story", "create", "--epic", "PAY-3", "Login"]).unwrap();
+        let Some(Command::Story(StoryCommand::Create { epic, story })) = cli.command else {
+            panic!("expected story create");
+        };
+        assert_eq!((epic.prefix.as_deref(), epic.id), (Some("PAY"), 3));
+        assert_eq!(story.name, "Login");
+        assert_eq!(story.priority, Priority::Medium);
+
+        let cli = Cli::try_parse_from(["jira", "epic", "create", "Billing", "--priority", "HIGH"])
+            .unwrap();
+        let Some(Command::Epic(EpicCommand::Create(epic))) = cli.command else {
+            panic!("expected epic create");
+        };
+        assert_eq!(epic.priority, Priority::High);
+        assert!(Cli::try_parse_from(["jira", "epic", "priority", "1", "urgent"]).is_err());
+
+        assert!(Cli::try_parse_from(["jira"]).unwrap().command.is_none());
+        assert!(Cli::try_parse_from(["jira", "story", "status", "7", "done"]).is_err());
+        assert!(Cli::try_parse_from(["jira", "story", "show", "PAY17"]).is_err());
+        assert!(Cli::try_parse_from(["jira", "epic", "due", "1", "+3d"]).is_ok());
+        assert!(Cli::try_parse_from(["jira", "epic", "due", "1", "soon"]).is_err());
+        assert!(Cli::try_parse_from(["jira", "story", "describe", "2", "--edit"]).is_ok());
+        assert!(Cli::try_parse_from(["jira", "story", "describe", "2"]).is_err());
+        assert!(Cli::try_parse_from(["jira", "story", "describe", "2", "Text", "-e"]).is_err());
+    }
+
+    #[test]
+    fn commands_should_update_the_database() {
+        let db = build_db();
+
+        let create_epic = EpicCommand::Create(NewItem {
+            name: "Epic".to_owned(),
+            description: "".to_owned(),
+            priority: Priority::Low,
+            due: None,
+        });
+        assert_eq!(
+            run_epic_command(&db, create_epic).unwrap(),
+            Some("CreateEpic")
+        );
+        let create_story = StoryCommand::Create {
+            epic: key("PAY-1"),
+            story: NewItem {
+                name: "Story".to_owned(),
+                description: "".to_owned(),
+                priority: Priority::Medium,
+                due: NaiveDate::from_ymd_opt(2023, 5, 19),
+            },
+        };
+        run_story_command(&db, create_story).unwrap();
+        assert_eq!(db.read().unwrap().epics[&1].priority, Priority::Low);
+
+        let due = StoryCommand::RemoveDue {
+            story_id: key("PAY-2"),
+        };
+        assert_eq!(
+            run_story_command(&db, due).unwrap(),
+            Some("RemoveStoryDueDate")
+        );
+        assert_eq!(db.read().unwrap().stories[&2].due_date, None);
+
+        let priority = StoryCommand::Priority {
+            story_id: key("PAY-2"),
+            priority: Priority::Highest,
+        };
+        assert_eq!(
+            run_story_command(&db, priority).unwrap(),
+            Some("UpdateStoryPriority")
+        );
+        assert_eq!(db.read().unwrap().stories[&2].priority, Priority::Highest);
+
+        let status = StoryCommand::Status {
+            story_id: key("pay-2"),
+            status: Status::Closed,
+        };
+        run_story_command(&db, status).unwrap();
+        assert_eq!(db.read().unwrap().stories[&2].status, Status::Closed);
+
+        let status = StoryCommand::Status {
+            story_id: key("OPS-2"),
+            status: Status::Open,
+        };
+        assert!(run_story_command(&db, status).is_err());
+
+        let delete = StoryCommand::Delete { story_id: key("2") };
+        assert_eq!(run_story_command(&db, delete).unwrap(), Some("DeleteStory"));
+        let db_state = db.read().unwrap();
+        assert!(db_state.stories.is_empty());
+        assert!(db_state.epics[&1].stories.is_empty());
+    }
+
+    #[test]
+    fn user_commands_should_manage_assignments() {
+        let db = build_db();
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let assign = StoryCommand::Assign {
+            story_id: key("PAY-2"),
+            username: "alice".to_owned(),
+        };
+        assert!(run_story_command(&db, assign).is_err());
+
+        let add = UserCommand::Add {
+            username: "alice".to_owned(),
+            name: "Alice".to_owned(),
+        };
+        assert_eq!(run_user_command(&db, add).unwrap(), Some("AddUser"));
+        let assign = StoryCommand::Assign {
+            story_id: key("PAY-2"),
+            username: "alice".to_owned(),
+        };
+        assert_eq!(run_story_command(&db, assign).unwrap(), Some("AssignStory"));
+        let owner = EpicCommand::Owner {
+            epic_id: key("PAY-1"),
+            username: "alice".to_owned(),
+        };
+        run_epic_command(&db, owner).unwrap();
+        let db_state = db.read().unwrap();
+        assert_eq!(
+            db_state.stories[&story_id].assignee.as_deref(),
+            Some("alice")
+        );
+        assert_eq!(db_state.epics[&epic_id].owner.as_deref(), Some("alice"));
+
+        let remove = UserCommand::Remove {
+            username: "alice".to_owned(),
+        };
+        assert_eq!(run_user_command(&db, remove).unwrap(), Some("RemoveUser"));
+        let db_state = db.read().unwrap();
+        assert!(db_state.users.is_empty());
+        assert_eq!(db_state.stories[&story_id].assignee, None);
+        assert_eq!(db_state.epics[&epic_id].owner, None);
+    }
+
+    #[test]
+    fn label_commands_should_add_and_remove_labels() {
+        let db = build_db();
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let label = EpicCommand::Label {
+            epic_id: key("PAY-1"),
+            label: "backend".to_owned(),
+        };
+        assert_eq!(run_epic_command(&db, label).unwrap(), Some("AddEpicLabel"));
+        let label = StoryCommand::Label {
+            story_id: key("PAY-2"),
+            label: "tech debt".to_owned(),
+        };
+        assert!(run_story_command(&db, label).is_err());
+        let label = StoryCommand::Label {
+            story_id: key("PAY-2"),
+            label: "tech-debt".to_owned(),
+        };
+        assert_eq!(
+            run_story_command(&db, label).unwrap(),
+            Some("AddStoryLabel")
+        );
+        let unlabel = EpicCommand::Unlabel {
+            epic_id: key("PAY-1"),
+            label: "backend".to_owned(),
+        };
+        assert_eq!(
+            run_epic_command(&db, unlabel).unwrap(),
+            Some("RemoveEpicLabel")
+        );
+
+        let db_state = db.read().unwrap();
+        assert!(db_state.epics[&epic_id].labels.is_empty());
+        assert!(db_state.stories[&story_id].labels.contains("tech-debt"));
+        let unlabel = StoryCommand::Unlabel {
+            story_id: key("PAY-2"),
+            label: "backend".to_owned(),
+        };
+        assert!(run_story_command(&db, unlabel).is_err());
+    }
+
+    #[test]
+    fn rename_and_describe_should_update_items() {
+        let db = build_db();
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let rename = EpicCommand::Rename {
+            epic_id: key("PAY-1"),
+            name: "Payments".to_owned(),
+        };
+        assert_eq!(
+            run_epic_command(&db, rename).unwrap(),
+            Some("UpdateEpicName")
+        );
+        let describe = StoryCommand::Describe {
+            story_id: key("PAY-2"),
+            description: NewDescription {
+                text: Some("Card form".to_owned()),
+                edit: false,
+            },
+        };
+        assert_eq!(
+            run_story_command(&db, describe).unwrap(),
+            Some("UpdateStoryDescription")
+        );
+        let rename = StoryCommand::Rename {
+            story_id: key("PAY-2"),
+            name: " ".to_owned(),
+        };
+        assert!(run_story_command(&db, rename).is_err());
+
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.epics[&epic_id].name, "Payments");
+        assert_eq!(db_state.stories[&story_id].description, "Card form");
+    }
+
+    #[test]
+    fn move_should_link_the_story_to_the_other_epic() {
+        let db = build_db();
+        let from = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), from)
+            .unwrap();
+        let to = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let move_story = StoryCommand::Move {
+            story_id: key("PAY-2"),
+            epic: key("PAY-3"),
+        };
+        assert_eq!(
+            run_story_command(&db, move_story).unwrap(),
+            Some("MoveStory")
+        );
+        let db_state = db.read().unwrap();
+        assert!(db_state.epics[&from].stories.is_empty());
+        assert_eq!(db_state.epics[&to].stories, vec![story_id]);
+    }
+
+    #[test]
+    fn rank_should_reorder_the_epics_stories() {
+        let db = build_db();
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let [a, b, c] = [(); 3].map(|_| {
+            db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap()
+        });
+
+        let cli =
+            Cli::try_parse_from(["jira", "story", "rank", "PAY-4", "--before", "PAY-2"]).unwrap();
+        let Some(Command::Story(rank)) = cli.command else {
+            panic!("expected story rank");
+        };
+        assert_eq!(
+            run_story_command(&db, rank).unwrap(),
+            Some("RankStoryBefore")
+        );
+        assert_eq!(db.read().unwrap().epics[&epic_id].stories, vec![c, a, b]);
+
+        assert!(Cli::try_parse_from(["jira", "story", "rank", "PAY-4"]).is_err());
+        assert!(Cli::try_parse_from(["jira", "story", "rank", "PAY-4", "--top", "--up"]).is_err());
+    }
+
+    #[test]
+    fn comment_commands_should_change_the_thread() {
+        let db = build_db();
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let comment = StoryCommand::Comment {
+            story_id: key("PAY-2"),
+            body: "Blocked on review".to_owned(),
+        };
+        assert_eq!(
+            run_story_command(&db, comment).unwrap(),
+            Some("CommentOnStory")
+        );
+        let edit = StoryCommand::EditComment {
+            story_id: key("PAY-2"),
+            comment_id: 1,
+            body: "Reviewed".to_owned(),
+        };
+        assert_eq!(run_story_command(&db, edit).unwrap(), Some("EditComment"));
+        assert_eq!(
+            db.read().unwrap().stories[&story_id].comments[0].body,
+            "Reviewed"
+        );
+
+        let delete = StoryCommand::DeleteComment {
+            story_id: key("PAY-2"),
+            comment_id: 1,
+        };
+        assert_eq!(
+            run_story_command(&db, delete).unwrap(),
+            Some("DeleteComment")
+        );
+        assert!(db.read().unwrap().stories[&story_id].comments.is_empty());
+    }
+
+    #[test]
+    fn read_commands_should_not_record_changes() {
+        let db = build_db();
+        let list = EpicCommand::List {
+            label: None,
+            format: Format::Json,
+            sort: SortBy::Priority,
+        };
+        assert_eq!(run_epic_command(&db, list).unwrap(), None);
+        let show = EpicCommand::Show {
+            epic_id: key("PAY-1"),
+            format: Format::Table,
+        };
+        assert!(run_epic_command(&db, show).is_err());
+        let list = StoryCommand::List {
+            epic: Some(key("1")),
+            assignee: None,
+            label: None,
+            format: Format::Ndjson,
+            sort: SortBy::Id,
+        };
+        assert!(run_story_command(&db, list).is_err());
+        assert!(db.take_last_write().is_none());
+    }
+
+    #[test]
+    fn cli_should_parse_global_database_options() {
+        let cli = Cli::try_parse_from(["jira", "epic", "list", "--project", "payments"]).unwrap();
+        assert_eq!(cli.project.as_deref(), Some("payments"));
+
+        let cli = Cli::try_parse_from(["jira", "--db", "boards/db.sqlite", "log"]).unwrap();
+        assert_eq!(cli.db, Some(PathBuf::from("boards/db.sqlite")));
+    }
+
+    #[test]
+    fn migration_target_should_default_to_a_sibling_file() {
+        assert_eq!(
+            migration_target(Path::new("boards/payments.json"), None, "sqlite").unwrap(),
+            PathBuf::from("boards/payments.sqlite")
+        );
+        assert_eq!(
+            migration_target(Path::new(config::JSON_DB_PATH), None, "log").unwrap(),
+            PathBuf::from(config::EVENT_LOG_PATH)
+        );
+        assert_eq!(
+            migration_target(Path::new("a.json"), Some("b.log".into()), "log").unwrap(),
+            PathBuf::from("b.log")
+        );
+        assert!(migration_target(Path::new("a.sqlite"), None, "log").is_err());
+        assert!(event_log(Path::new("a.json")).is_err());
+    }
+
+    #[test]
+    fn parse_point_in_time_should_accept_dates_and_timestamps() {
+        let at = parse_point_in_time("2023-05-19").unwrap();
+        assert_eq!(at.to_rfc3339(), "2023-05-19T23:59:59+00:00");
+
+        let at = parse_point_in_time("2023-05-19T10:00:00+02:00").unwrap();
+        assert_eq!(at.to_rfc3339(), "2023-05-19T08:00:00+00:00");
+
+        assert!(parse_point_in_time("yesterday").is_err());
+    }
+}
@@ -0,0 +1,391 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::models::{Comment, DBState, KeyPrefix, Priority, Status, User};
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Format {
+    /// Human readable rows
+    #[default]
+    Table,
+    /// A single pretty-printed json document
+    Json,
+    /// One json object per line
+    Ndjson,
+}
+
+// The json shapes of the records are documented in the README and only ever gain fields,
+// so scripts can rely on them.
+
+#[derive(Serialize, PartialEq, Eq, Debug)]
+pub struct EpicRecord {
+    pub id: u32,
+    /// `PAY-1`, or the id as a string when the project has no key prefix.
+    pub key: String,
+    pub name: String,
+    pub description: String,
+    pub status: &'static str,
+    pub priority: &'static str,
+    /// Username, `null` when nobody owns the epic.
+    pub owner: Option<String>,
+    /// Sorted by name.
+    pub labels: Vec<String>,
+    pub due_date: Option<NaiveDate>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub created_by: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// In the epic's order.
+    pub stories: Vec<StoryRecord>,
+}
+
+#[derive(Serialize, PartialEq, Eq, Debug)]
+pub struct StoryRecord {
+    pub id: u32,
+    pub key: String,
+    pub epic_id: Option<u32>,
+    pub name: String,
+    pub description: String,
+    pub status: &'static str,
+    pub priority: &'static str,
+    pub assignee: Option<String>,
+    pub labels: Vec<String>,
+    pub due_date: Option<NaiveDate>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub created_by: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Oldest first.
+    pub comments: Vec<CommentRecord>,
+}
+
+#[derive(Serialize, PartialEq, Eq, Debug)]
+pub struct CommentRecord {
+    pub id: u32,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    /// `null` unless the comment was edited.
+    pub edited_at: Option<DateTime<Utc>>,
+    pub body: String,
+}
+
+#[derive(Serialize, PartialEq, Eq, Debug)]
+pub struct UserRecord {
+    pub username: String,
+    pub name: String,
+}
+
+impl EpicRecord {
+    pub fn new(db_state: &DBState, keys: &KeyPrefix, epic_id: u32) -> Result<Self> {
+        let epic = db_state
+            .epics
+            .get(&epic_id)
+            .ok_or_else(|| anyhow!("Epic {} not found", keys.key(epic_id)))?;
+        let stories = epic
+            .stories
+            .iter()
+            .filter(|story_id| db_state.stories.contains_key(story_id))
+            .map(|story_id| StoryRecord::new(db_state, keys, *story_id, Some(epic_id)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            id: epic_id,
+            key: keys.key(epic_id),
+            name: epic.name.clone(),
+            description: epic.description.clone(),
+            status: status_name(&epic.status),
+            priority: priority_name(&epic.priority),
+            owner: epic.owner.clone(),
+            labels: epic.labels.iter().cloned().collect(),
+            due_date: epic.due_date,
+            created_at: epic.created_at,
+            created_by: epic.created_by.clone(),
+            updated_at: epic.updated_at,
+            stories,
+        })
+    }
+}
+
+impl StoryRecord {
+    pub fn new(
+        db_state: &DBState,
+        keys: &KeyPrefix,
+        story_id: u32,
+        epic_id: Option<u32>,
+    ) -> Result<Self> {
+        let story = db_state
+            .stories
+            .get(&story_id)
+            .ok_or_else(|| anyhow!("Story {} not found", keys.key(story_id)))?;
+
+        Ok(Self {
+            id: story_id,
+            key: keys.key(story_id),
+            epic_id,
+            name: story.name.clone(),
+            description: story.description.clone(),
+            status: status_name(&story.status),
+            priority: priority_name(&story.priority),
+            assignee: story.assignee.clone(),
+            labels: story.labels.iter().cloned().collect(),
+            due_date: story.due_date,
+            created_at: story.created_at,
+            created_by: story.created_by.clone(),
+            updated_at: story.updated_at,
+            comments: story.comments.iter().map(CommentRecord::new).collect(),
+        })
+    }
+}
+
+impl CommentRecord {
+    pub fn new(comment: &Comment) -> Self {
+        Self {
+            id: comment.id,
+            author: comment.author.clone(),
+            created_at: comment.created_at,
+            edited_at: comment.edited_at,
+            body: comment.body.clone(),
+        }
+    }
+}
+
+impl UserRecord {
+    pub fn new(username: &str, user: &User) -> Self {
+        Self {
+            username: username.to_owned(),
+            name: user.name.clone(),
+        }
+    }
+}
+
+/// The epic each story belongs to.
+pub fn story_epics(db_state: &DBState) -> HashMap<u32, u32> {
+    db_state
+        .epics
+        .iter()
+        .flat_map(|(epic_id, epic)| epic.stories.iter().map(|story_id| (*story_id, *epic_id)))
+        .collect()
+}
+
+/// Status names in output are stable, unlike the `Display` text.
+pub fn status_name(status: &Status) -> &'static str {
+    match status {
+        Status::Open => "open",
+        Status::InProgress => "in-progress",
+        Status::Resolved => "resolved",
+        Status::Closed => "closed",
+    }
+}
+
+pub fn priority_name(priority: &Priority) -> &'static str {
+    match priority {
+        Priority::Highest => "highest",
+        Priority::High => "high",
+        Priority::Medium => "medium",
+        Priority::Low => "low",
+        Priority::Lowest => "lowest",
+    }
+}
+
+pub fn print_epics(records: &[EpicRecord], format: Format) -> Result<()> {
+    print_list(records, format, |epic| println!("{}", epic_row(epic)))
+}
+
+pub fn print_epic(record: &EpicRecord, format: Format) -> Result<()> {
+    print_one(record, format, |epic| {
+        print_item(&epic_row(epic), &epic.description);
+        for story in &epic.stories {
+            println!("    {}", story_row(story));
+        }
+    })
+}
+
+pub fn print_stories(records: &[StoryRecord], format: Format) -> Result<()> {
+    print_list(records, format, |story| println!("{}", story_row(story)))
+}
+
+pub fn print_story(record: &StoryRecord, format: Format) -> Result<()> {
+    print_one(record, format, |story| {
+        print_item(&story_row(story), &story.description);
+        for comment in &story.comments {
+            println!(
+                "    #{} {} {}: {}",
+                comment.id,
+                comment.author,
+                comment.created_at.to_rfc3339(),
+                comment.body
+            );
+        }
+    })
+}
+
+pub fn print_users(records: &[UserRecord], format: Format) -> Result<()> {
+    print_list(records, format, |user| match user.name.as_str() {
+        "" => println!("{}", user.username),
+        name => println!("{} | {name}", user.username),
+    })
+}
+
+fn epic_row(epic: &EpicRecord) -> String {
+    let owner = epic.owner.as_deref().unwrap_or("-");
+    format!(
+        "{} | {} | {owner} | {} | {} | {} | {}",
+        epic.key,
+        epic.name,
+        labels_cell(&epic.labels),
+        due_date_cell(epic.due_date),
+        epic.priority,
+        epic.status
+    )
+}
+
+fn story_row(story: &StoryRecord) -> String {
+    let assignee = story.assignee.as_deref().unwrap_or("-");
+    format!(
+        "{} | {} | {assignee} | {} | {} | {} | {}",
+        story.key,
+        story.name,
+        labels_cell(&story.labels),
+        due_date_cell(story.due_date),
+        story.priority,
+        story.status
+    )
+}
+
+fn due_date_cell(due_date: Option<NaiveDate>) -> String {
+    due_date.map_or_else(|| "-".to_owned(), |due_date| due_date.to_string())
+}
+
+fn labels_cell(labels: &[String]) -> String {
+    match labels.is_empty() {
+        true => "-".to_owned(),
+        false => labels.join(","),
+    }
+}
+
+fn print_item(row: &str, description: &str) {
+    println!("{row}");
+    if !description.is_empty() {
+        println!("{description}");
+    }
+}
+
+fn print_list<T: Serialize>(records: &[T], format: Format, print_row: impl Fn(&T)) -> Result<()> {
+    match format {
+        Format::Table => records.iter().for_each(print_row),
+        Format::Json => println!("{}", serde_json::to_string_pretty(records)?),
+        Format::Ndjson => {
+            for record in records {
+                println!("{}", serde_json::to_string(record)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_one<T: Serialize>(record: &T, format: Format, print_table: impl Fn(&T)) -> Result<()> {
+    match format {
+        Format::Table => print_table(record),
+        Format::Json => println!("{}", serde_json::to_string_pretty(record)?),
+        Format::Ndjson => println!("{}", serde_json::to_string(record)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Epic, Story};
+
+    fn build_state() -> DBState {
+        let mut epic = Epic::new("Payments".to_owned(), "".to_owned());
+        epic.stories = vec![3, 2];
+        epic.created_at = None;
+        epic.created_by = None;
+        epic.updated_at = None;
+
+        let mut story = Story::new("Checkout".to_owned(), "Card form".to_owned());
+        story.status = Status::InProgress;
+        story.priority = Priority::High;
+        story.assignee = Some("bob".to_owned());
+        story.due_date = NaiveDate::from_ymd_opt(2023, 5, 19);
+        story.comments.push(Comment {
+            id: 1,
+            author: "bob".to_owned(),
+            created_at: "2023-05-19T08:00:00Z".parse().unwrap(),
+            edited_at: None,
+            body: "Done?".to_owned(),
+        });
+        story.labels = ["tech-debt", "backend"].map(str::to_owned).into();
+        story.created_at = None;
+        story.created_by = Some("alice".to_owned());
+        story.updated_at = None;
+
+        let mut db_state = DBState::default();
+        db_state.epics.insert(1, epic);
+        db_state.stories.insert(3, story);
+        db_state
+    }
+
+    #[test]
+    fn epic_record_should_have_a_stable_json_shape() {
+        let keys = KeyPrefix::new("PAY").unwrap();
+        let record = EpicRecord::new(&build_state(), &keys, 1).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&record).unwrap(),
+            serde_json::json!({
+                "id": 1,
+                "key": "PAY-1",
+                "name": "Payments",
+                "description": "",
+                "status": "open",
+                "priority": "medium",
+                "owner": null,
+                "labels": [],
+                "due_date": null,
+                "created_at": null,
+                "created_by": null,
+                "updated_at": null,
+                "stories": [{
+                    "id": 3,
+                    "key": "PAY-3",
+                    "epic_id": 1,
+                    "name": "Checkout",
+                    "description": "Card form",
+                    "status": "in-progress",
+                    "priority": "high",
+                    "assignee": "bob",
+                    "labels": ["backend", "tech-debt"],
+                    "due_date": "2023-05-19",
+                    "created_at": null,
+                    "created_by": "alice",
+                    "updated_at": null,
+                    "comments": [{
+                        "id": 1,
+                        "author": "bob",
+                        "created_at": "2023-05-19T08:00:00Z",
+                        "edited_at": null,
+                        "body": "Done?",
+                    }],
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn records_should_fail_for_missing_items() {
+        let db_state = build_state();
+        let keys = KeyPrefix::default();
+        assert!(EpicRecord::new(&db_state, &keys, 3).is_err());
+        assert!(StoryRecord::new(&db_state, &keys, 2, None).is_err());
+    }
+
+    #[test]
+    fn story_epics_should_map_stories_to_their_epic() {
+        let story_epics = story_epics(&build_state());
+        assert_eq!(story_epics, HashMap::from_iter([(3, 1), (2, 1)]));
+    }
+}
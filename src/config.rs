@@ -0,0 +1,372 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::TimeDelta;
+use serde::Deserialize;
+
+use crate::{
+    db::{self, JiraDatabase},
+    models::{KeyPrefix, User},
+};
+
+/// Under `$XDG_CONFIG_HOME`, or `~/.config` when it isn't set.
+const CONFIG_FILE: &str = "jira-cli/config.toml";
+/// Looked up in the working directory and its parents, overrides the user's config.
+const LOCAL_CONFIG_FILE: &str = ".jira-cli.toml";
+const TRASH_RETENTION_VAR: &str = "JIRA_TRASH_RETENTION_DAYS";
+
+// Without any configured project the board lives in `data/`, using whichever of these
+// files exists first.
+pub const EVENT_LOG_PATH: &str = "data/events.log";
+pub const SQLITE_DB_PATH: &str = "data/db.sqlite";
+pub const JSON_DB_PATH: &str = "data/db.json";
+
+#[derive(Deserialize, Default, PartialEq, Eq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub default_project: Option<String>,
+    pub trash_retention_days: Option<u32>,
+    /// Your username on the boards, for "My work" and as the author of changes. Defaults
+    /// to `$USER`.
+    pub user: Option<String>,
+    #[serde(default)]
+    pub projects: BTreeMap<String, ProjectConfig>,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Relative paths are resolved against the directory of the config file.
+    pub db: PathBuf,
+    /// Prefix of the item keys, `PAY` for `PAY-17`. Defaults to the first three letters
+    /// of the project name.
+    pub key: Option<String>,
+}
+
+impl ProjectConfig {
+    /// A database opened directly, its items are shown by id.
+    pub fn from_path(db: PathBuf) -> Self {
+        Self { db, key: None }
+    }
+}
+
+/// Which board to open.
+#[derive(PartialEq, Eq, Debug)]
+pub enum Selection {
+    Database(ProjectConfig),
+    /// Several projects are configured and none was picked.
+    Projects(Vec<String>),
+}
+
+impl Selection {
+    pub fn database(self) -> Result<ProjectConfig> {
+        match self {
+            Selection::Database(project) => Ok(project),
+            Selection::Projects(names) => Err(anyhow!(
+                "Several projects are configured, pick one with --project: {}",
+                names.join(", ")
+            )),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the user's config, then the project-local one on top of it.
+    pub fn load() -> Result<Self> {
+        let mut config = Self::default();
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+
+        if let Some(config_dir) = config_dir {
+            let path = config_dir.join(CONFIG_FILE);
+            if path.exists() {
+                config.merge(Self::from_file(&path)?);
+            }
+        }
+
+        let current_dir = env::current_dir().context("Failed to get the working directory")?;
+        let local_path = current_dir
+            .ancestors()
+            .map(|dir| dir.join(LOCAL_CONFIG_FILE))
+            .find(|path| path.exists());
+        if let Some(path) = local_path {
+            config.merge(Self::from_file(&path)?);
+        }
+
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        let mut config: Self = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse config {}", path.display()))?;
+
+        if let Some(user) = &config.user {
+            User::validate_username(user)
+                .with_context(|| format!("Invalid user in {}", path.display()))?;
+        }
+
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        for (name, project) in config.projects.iter_mut() {
+            project.db = resolve_path(base_dir, &project.db);
+            // Keys are shown upper-cased however they were configured, like default ones.
+            match &mut project.key {
+                Some(key) => {
+                    KeyPrefix::new(key).with_context(|| {
+                        format!("Invalid key of project {name} in {}", path.display())
+                    })?;
+                    *key = key.to_uppercase();
+                }
+                None => project.key = default_key(name),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Values in `other` win, projects are merged by name.
+    fn merge(&mut self, other: Self) {
+        if other.default_project.is_some() {
+            self.default_project = other.default_project;
+        }
+        if other.trash_retention_days.is_some() {
+            self.trash_retention_days = other.trash_retention_days;
+        }
+        if other.user.is_some() {
+            self.user = other.user;
+        }
+        self.projects.extend(other.projects);
+    }
+
+    /// An explicit `--db` (or `JIRA_DB`) wins over `--project`, then the default project.
+    /// Without any configured project the `data/` directory is used, as it always was.
+    pub fn select(&self, db: Option<PathBuf>, project: Option<&str>) -> Result<Selection> {
+        if let Some(db) = db {
+            return Ok(Selection::Database(ProjectConfig::from_path(db)));
+        }
+        if let Some(name) = project.or(self.default_project.as_deref()) {
+            return Ok(Selection::Database(self.project(name)?));
+        }
+
+        match self.projects.len() {
+            0 => Ok(Selection::Database(ProjectConfig::from_path(
+                legacy_database(),
+            ))),
+            1 => Ok(Selection::Database(
+                self.projects.values().next().unwrap().clone(),
+            )),
+            _ => Ok(Selection::Projects(self.projects.keys().cloned().collect())),
+        }
+    }
+
+    pub fn project(&self, name: &str) -> Result<ProjectConfig> {
+        self.projects
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown project \"{name}\""))
+    }
+
+    pub fn open_database(&self, project: &ProjectConfig) -> Result<JiraDatabase> {
+        let path = &project.db;
+        let mut db = JiraDatabase::open(path)
+            .with_context(|| format!("Failed to open database {}", path.display()))?;
+        db.set_trash_retention(self.trash_retention()?);
+        if let Some(key) = &project.key {
+            db.set_key_prefix(KeyPrefix::new(key)?);
+        }
+        if let Some(user) = &self.user {
+            db.set_current_user(user.clone());
+        }
+        Ok(db)
+    }
+
+    /// `JIRA_TRASH_RETENTION_DAYS` overrides the config file.
+    fn trash_retention(&self) -> Result<TimeDelta> {
+        let days = match env::var(TRASH_RETENTION_VAR) {
+            Ok(days) => days
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| {
+                    anyhow!("Expected a number of days in {TRASH_RETENTION_VAR}, got \"{days}\"")
+                })?
+                .into(),
+            Err(_) => self
+                .trash_retention_days
+                .map_or(db::DEFAULT_TRASH_RETENTION_DAYS, i64::from),
+        };
+        Ok(TimeDelta::days(days))
+    }
+}
+
+/// Each database keeps its own undo history next to it.
+pub fn undo_history_path(db_path: &Path) -> String {
+    format!("{}.undo.json", db_path.display())
+}
+
+/// `PAY` for "payments", `None` when the name doesn't start with a letter.
+fn default_key(project_name: &str) -> Option<String> {
+    let key = project_name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(3)
+        .collect::<String>();
+    KeyPrefix::new(&key).ok().map(|_| key.to_uppercase())
+}
+
+fn legacy_database() -> PathBuf {
+    [EVENT_LOG_PATH, SQLITE_DB_PATH]
+        .into_iter()
+        .find(|path| Path::new(path).exists())
+        .unwrap_or(JSON_DB_PATH)
+        .into()
+}
+
+/// Expands a leading `~/` and makes relative paths relative to `base_dir`.
+fn resolve_path(base_dir: &Path, path: &Path) -> PathBuf {
+    if let (Ok(rest), Some(home)) = (path.strip_prefix("~"), env::var_os("HOME")) {
+        return Path::new(&home).join(rest);
+    }
+    base_dir.join(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_config(dir: &Path, contents: &str) -> PathBuf {
+        let path = dir.join("config.toml");
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn from_file_should_resolve_project_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(
+            dir.path(),
+            r#"
+            default_project = "payments"
+            user = "alice"
+
+            [projects.payments]
+            db = "boards/payments.json"
+
+            [projects.platform]
+            db = "/srv/platform.sqlite"
+            key = "ops"
+            "#,
+        );
+
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.default_project.as_deref(), Some("payments"));
+        assert_eq!(config.user.as_deref(), Some("alice"));
+        assert_eq!(
+            config.projects["payments"].db,
+            dir.path().join("boards/payments.json")
+        );
+        assert_eq!(
+            config.projects["platform"].db,
+            PathBuf::from("/srv/platform.sqlite")
+        );
+        assert_eq!(config.projects["payments"].key.as_deref(), Some("PAY"));
+        assert_eq!(config.projects["platform"].key.as_deref(), Some("OPS"));
+    }
+
+    #[test]
+    fn from_file_should_reject_invalid_keys_and_users() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(
+            dir.path(),
+            "[projects.payments]\ndb = \"a.json\"\nkey = \"PAY-1\"",
+        );
+        assert!(Config::from_file(&path).is_err());
+
+        let path = write_config(dir.path(), "user = \"alice smith\"");
+        assert!(Config::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn from_file_should_reject_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(dir.path(), "default_projct = \"payments\"");
+        assert!(Config::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn merge_should_let_the_local_config_win() {
+        let project = |db: &str| ProjectConfig::from_path(db.into());
+        let mut config = Config {
+            default_project: Some("payments".to_owned()),
+            trash_retention_days: Some(7),
+            user: Some("alice".to_owned()),
+            projects: BTreeMap::from_iter([
+                ("payments".to_owned(), project("payments.json")),
+                ("platform".to_owned(), project("platform.json")),
+            ]),
+        };
+        config.merge(Config {
+            default_project: None,
+            trash_retention_days: Some(1),
+            user: None,
+            projects: BTreeMap::from_iter([("payments".to_owned(), project("local.json"))]),
+        });
+
+        assert_eq!(config.default_project.as_deref(), Some("payments"));
+        assert_eq!(config.trash_retention_days, Some(1));
+        assert_eq!(config.user.as_deref(), Some("alice"));
+        assert_eq!(config.projects["payments"].db, PathBuf::from("local.json"));
+        assert_eq!(config.projects.len(), 2);
+    }
+
+    #[test]
+    fn select_should_prefer_explicit_choices() {
+        let project = |db: &str| ProjectConfig::from_path(db.into());
+        let mut config = Config {
+            projects: BTreeMap::from_iter([
+                ("payments".to_owned(), project("payments.json")),
+                ("platform".to_owned(), project("platform.json")),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.select(None, None).unwrap(),
+            Selection::Projects(vec!["payments".to_owned(), "platform".to_owned()])
+        );
+        assert_eq!(
+            config
+                .select(Some("other.json".into()), Some("payments"))
+                .unwrap(),
+            Selection::Database(project("other.json"))
+        );
+        assert_eq!(
+            config.select(None, Some("platform")).unwrap(),
+            Selection::Database(project("platform.json"))
+        );
+        assert!(config.select(None, Some("unknown")).is_err());
+
+        config.default_project = Some("payments".to_owned());
+        assert_eq!(
+            config.select(None, None).unwrap(),
+            Selection::Database(project("payments.json"))
+        );
+    }
+
+    #[test]
+    fn select_should_fall_back_to_the_data_directory() {
+        let config = Config::default();
+        assert!(matches!(
+            config.select(None, None).unwrap(),
+            Selection::Database(_)
+        ));
+        assert!(Selection::Projects(vec!["a".to_owned()])
+            .database()
+            .is_err());
+    }
+}
@@ -1,164 +1,1180 @@
-use std::fs;
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    fmt::Display,
+    fs::{self, TryLockError},
+    io::{self, Write},
+    path::Path,
+    process, thread,
+    time::Duration,
+};
 
 use anyhow::{anyhow, Context, Result};
+use chrono::{NaiveDate, TimeDelta, Utc};
+use serde_json::{Map, Value};
 
-use crate::models::{DBState, Epic, Status, Story};
+use crate::io_utils::current_user;
+use crate::models::{
+    validate_label, Comment, DBState, Epic, FieldChange, KeyPrefix, Priority, Status, Story,
+    StoryRank, TrashedEpic, TrashedStory, User,
+};
+
+mod event_log;
+mod sqlite;
+
+pub use event_log::EventLogDatabase;
+pub use sqlite::SqliteDatabase;
+
+pub const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
 
 pub struct JiraDatabase {
     pub database: Box<dyn Database>,
+    /// Revision of the last state handed out by `read`. Mutations are rejected when the
+    /// stored revision moved past it, since the caller acted on stale data.
+    observed_revision: Cell<Option<u64>>,
+    /// The states before and after the most recent mutation, for the undo history.
+    last_write: RefCell<Option<(DBState, DBState)>>,
+    /// How long deleted items stay in the trash. Expired ones are purged on the next
+    /// mutation.
+    trash_retention: TimeDelta,
+    key_prefix: KeyPrefix,
+    /// Username whose work the "My work" page shows, and who changes, deletions and
+    /// comments are recorded as made by.
+    current_user: String,
 }
 
 impl JiraDatabase {
     pub fn new(file_path: String) -> Self {
+        Self::from_database(Box::new(JSONFileDatabase { file_path }))
+    }
+
+    pub fn new_sqlite(file_path: String) -> Result<Self> {
+        Ok(Self::from_database(Box::new(SqliteDatabase::open(
+            &file_path,
+        )?)))
+    }
+
+    pub fn new_event_log(file_path: String) -> Self {
+        Self::from_database(Box::new(EventLogDatabase::new(file_path)))
+    }
+
+    /// Picks the backend from the file extension: `.sqlite` or `.db` for SQLite, `.log`
+    /// for an event log and json otherwise. A missing json file starts an empty board.
+    pub fn open(path: &Path) -> Result<Self> {
+        let file_path = path.to_string_lossy().into_owned();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("sqlite" | "db") => Self::new_sqlite(file_path),
+            Some("log") => Ok(Self::new_event_log(file_path)),
+            _ => {
+                if !path.exists() {
+                    JSONFileDatabase {
+                        file_path: file_path.clone(),
+                    }
+                    .write(&DBState::default())?;
+                }
+                Ok(Self::new(file_path))
+            }
+        }
+    }
+
+    pub fn from_database(database: Box<dyn Database>) -> Self {
+        let current_user = current_user();
+        database.set_author(&current_user);
         Self {
-            database: Box::new(JSONFileDatabase { file_path }),
+            database,
+            observed_revision: Cell::new(None),
+            last_write: RefCell::new(None),
+            trash_retention: TimeDelta::days(DEFAULT_TRASH_RETENTION_DAYS),
+            key_prefix: KeyPrefix::default(),
+            current_user,
         }
     }
 
+    pub fn set_trash_retention(&mut self, trash_retention: TimeDelta) {
+        self.trash_retention = trash_retention;
+    }
+
+    pub fn set_key_prefix(&mut self, key_prefix: KeyPrefix) {
+        self.key_prefix = key_prefix;
+    }
+
+    /// How the project's items are shown to users, `PAY-17`.
+    pub fn key_prefix(&self) -> &KeyPrefix {
+        &self.key_prefix
+    }
+
+    pub fn set_current_user(&mut self, username: String) {
+        self.database.set_author(&username);
+        self.current_user = username;
+    }
+
+    pub fn current_user(&self) -> &str {
+        &self.current_user
+    }
+
     pub fn read(&self) -> Result<DBState> {
+        let db_state = self.database.read()?;
+        self.observed_revision.set(Some(db_state.revision));
+        Ok(db_state)
+    }
+
+    /// Like `read`, but keeps the revision mutations are checked against. For handling
+    /// input on a page, which has to act on the revision the page was drawn at.
+    pub fn snapshot(&self) -> Result<DBState> {
         self.database.read()
     }
 
-    pub fn create_epic(&self, epic: Epic) -> Result<u32> {
-        let mut data = self
-            .database
-            .read()
-            .with_context(|| format!("Failed to read database on create_epic"))?;
+    pub fn create_epic(&self, mut epic: Epic) -> Result<u32> {
+        epic.created_by = Some(self.current_user.clone());
+        self.update("create_epic", |data| {
+            let new_epic_id = data.last_item_id + 1;
+            data.last_item_id = new_epic_id;
+            data.epics.insert(new_epic_id, epic);
+            Ok(new_epic_id)
+        })
+    }
 
-        let new_epic_id = data.last_item_id + 1;
-        data.last_item_id = new_epic_id;
-        data.epics.insert(new_epic_id, epic);
-        self.database
-            .write(&data)
-            .with_context(|| format!("Failed insert epic to database"))?;
-        Ok(new_epic_id)
+    pub fn create_story(&self, mut story: Story, epic_id: u32) -> Result<u32> {
+        story.created_by = Some(self.current_user.clone());
+        self.update("create_story", |data| {
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Failed to get epic with id: {}", epic_id));
+            };
+
+            let new_story_id = data.last_item_id + 1;
+            data.last_item_id = new_story_id;
+            epic.stories.push(new_story_id);
+            data.stories.insert(new_story_id, story);
+            Ok(new_story_id)
+        })
     }
 
-    pub fn create_story(&self, story: Story, epic_id: u32) -> Result<u32> {
-        let mut data = self
-            .database
-            .read()
-            .with_context(|| format!("Failed to read database on create_story"))?;
+    /// Moves the epic and its stories to the trash.
+    pub fn delete_epic(&self, epic_id: u32) -> Result<()> {
+        self.update("delete_epic", |data| {
+            let Some(epic) = data.epics.remove(&epic_id) else {
+                return Err(anyhow!(format!(
+                    "Fail to delete epic - inesistent - {epic_id}"
+                )));
+            };
 
-        let Some(epic) = data.epics.get_mut(&epic_id) else {
-            return Err(anyhow!("Failed to get epic with id: {}", epic_id));
-        };
+            let (deleted_at, deleted_by) = (Utc::now(), self.current_user.clone());
+            for story_id in &epic.stories {
+                if let Some(story) = data.stories.remove(story_id) {
+                    let trashed = TrashedStory {
+                        story,
+                        epic_id,
+                        deleted_at,
+                        deleted_by: deleted_by.clone(),
+                    };
+                    data.trash.stories.insert(*story_id, trashed);
+                }
+            }
+            let trashed = TrashedEpic {
+                epic,
+                deleted_at,
+                deleted_by,
+            };
+            data.trash.epics.insert(epic_id, trashed);
+            Ok(())
+        })
+    }
 
-        let new_story_id = data.last_item_id + 1;
-        data.last_item_id = new_story_id;
-        epic.stories.push(new_story_id);
-        data.stories.insert(new_story_id, story);
+    /// Unlinks the story from its epic and moves it to the trash.
+    pub fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
+        self.update("delete_story", |data| {
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Failed to delete story - epic not found"));
+            };
+            let story_index = epic
+                .stories
+                .iter()
+                .position(|id| id == &story_id)
+                .ok_or_else(|| anyhow!("story_id not registered inside the given epic"))?;
+            epic.stories.remove(story_index);
+
+            if let Some(story) = data.stories.remove(&story_id) {
+                let trashed = TrashedStory {
+                    story,
+                    epic_id,
+                    deleted_at: Utc::now(),
+                    deleted_by: self.current_user.clone(),
+                };
+                data.trash.stories.insert(story_id, trashed);
+            }
+            Ok(())
+        })
+    }
 
-        self.database
-            .write(&data)
-            .with_context(|| format!("Failed insert create story on database"))?;
+    /// Unlinks the story from its epic and appends it to the stories of `epic_id`.
+    pub fn move_story(&self, story_id: u32, epic_id: u32) -> Result<()> {
+        self.update("move_story", |data| {
+            let from_epic_id = data
+                .epic_of(story_id)
+                .ok_or_else(|| anyhow!("Story with {story_id} not found"))?;
+            if from_epic_id == epic_id {
+                return Ok(());
+            }
+            let Some(to_name) = data.epics.get(&epic_id).map(|epic| epic.name.clone()) else {
+                return Err(anyhow!("Epic with {epic_id} not found"));
+            };
 
-        Ok(new_story_id)
+            let mut from_name = String::new();
+            for (id, epic) in data.epics.iter_mut() {
+                if *id == from_epic_id {
+                    epic.stories.retain(|linked_id| *linked_id != story_id);
+                    from_name = epic.name.clone();
+                } else if *id == epic_id {
+                    epic.stories.push(story_id);
+                }
+            }
+            if let Some(story) = data.stories.get_mut(&story_id) {
+                story.record_change(FieldChange::new(
+                    "epic",
+                    from_name,
+                    to_name,
+                    &self.current_user,
+                ));
+            }
+            Ok(())
+        })
     }
 
-    pub fn delete_epic(&self, epic_id: u32) -> Result<()> {
-        let mut data = self
-            .database
-            .read()
-            .with_context(|| format!("Failed to read database on delete_epic"))?;
+    /// Changes the place of the story in its epic's ranking.
+    pub fn rank_story(&self, story_id: u32, rank: StoryRank) -> Result<()> {
+        self.update("rank_story", |data| {
+            let Some(epic) = data
+                .epic_of(story_id)
+                .and_then(|epic_id| data.epics.get_mut(&epic_id))
+            else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            let stories = &mut epic.stories;
+            let index = stories
+                .iter()
+                .position(|id| *id == story_id)
+                .ok_or_else(|| anyhow!("Story with {story_id} not found"))?;
+
+            let new_index = match rank {
+                StoryRank::Up => index.saturating_sub(1),
+                StoryRank::Down => (index + 1).min(stories.len() - 1),
+                StoryRank::Top => 0,
+                StoryRank::Before(before_id) if before_id == story_id => index,
+                StoryRank::Before(before_id) => {
+                    let before_index = stories
+                        .iter()
+                        .position(|id| *id == before_id)
+                        .ok_or_else(|| anyhow!("Story with {before_id} is not in the same epic"))?;
+                    // Taking the story out shifts the later ones up by one.
+                    if before_index > index {
+                        before_index - 1
+                    } else {
+                        before_index
+                    }
+                }
+            };
+            stories.remove(index);
+            stories.insert(new_index, story_id);
+            Ok(())
+        })
+    }
 
-        let Some(epic) = data.epics.get(&epic_id) else {
-            return Err(anyhow!(format!("Fail to delete epic - inesistent - {epic_id}")));
-        };
+    /// Brings the epic back together with the stories that were deleted along with it.
+    pub fn restore_epic(&self, epic_id: u32) -> Result<()> {
+        self.update("restore_epic", |data| {
+            let Some(TrashedEpic { mut epic, .. }) = data.trash.epics.remove(&epic_id) else {
+                return Err(anyhow!("Epic {epic_id} is not in the trash"));
+            };
 
-        for story_id in &epic.stories {
-            data.stories.remove(&story_id);
-        }
-        data.epics.remove(&epic_id);
+            epic.stories.retain(|story_id| {
+                if data.stories.contains_key(story_id) {
+                    return true;
+                }
+                match data.trash.stories.remove(story_id) {
+                    Some(trashed) => {
+                        data.stories.insert(*story_id, trashed.story);
+                        true
+                    }
+                    None => false,
+                }
+            });
+            data.epics.insert(epic_id, epic);
+            Ok(())
+        })
+    }
 
-        self.database
-            .write(&data)
-            .with_context(|| format!("Failed write deleted epic data"))
+    /// Brings the story back, linked into the epic it was deleted from.
+    pub fn restore_story(&self, story_id: u32) -> Result<()> {
+        self.update("restore_story", |data| {
+            let Some(trashed) = data.trash.stories.remove(&story_id) else {
+                return Err(anyhow!("Story {story_id} is not in the trash"));
+            };
+            let epic_id = trashed.epic_id;
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!(
+                    "Epic {epic_id} of story {story_id} is deleted, restore the epic first"
+                ));
+            };
+
+            epic.stories.push(story_id);
+            data.stories.insert(story_id, trashed.story);
+            Ok(())
+        })
     }
 
-    pub fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
-        let mut data = self
-            .database
-            .read()
-            .with_context(|| format!("Failed to read database on delete story"))?;
+    /// Permanently deletes a trashed epic, and the stories that were deleted with it.
+    pub fn purge_epic(&self, epic_id: u32) -> Result<()> {
+        self.update("purge_epic", |data| {
+            if data.trash.epics.remove(&epic_id).is_none() {
+                return Err(anyhow!("Epic {epic_id} is not in the trash"));
+            }
+            data.trash
+                .stories
+                .retain(|_, trashed| trashed.epic_id != epic_id);
+            Ok(())
+        })
+    }
 
-        let Some(epic) = data.epics.get_mut(&epic_id) else {
-            return Err(anyhow!(format!("Failed to delete story - epic not found")));
-        };
-        let story_index = epic
-            .stories
-            .iter()
-            .position(|id| id == &story_id)
-            .ok_or_else(|| anyhow!("story_id not registered inside the given epic"))?;
-        epic.stories.remove(story_index);
-        data.stories.remove(&story_id);
+    /// Permanently deletes a trashed story.
+    pub fn purge_story(&self, story_id: u32) -> Result<()> {
+        self.update("purge_story", |data| {
+            if data.trash.stories.remove(&story_id).is_none() {
+                return Err(anyhow!("Story {story_id} is not in the trash"));
+            }
+            Ok(())
+        })
+    }
 
-        self.database
-            .write(&data)
-            .with_context(|| format!("Failed to write deleted story data"))?;
-        Ok(())
+    pub fn update_epic_name(&self, epic_id: u32, name: &str) -> Result<()> {
+        let name = item_name(name)?;
+        self.update("update_epic_name", |data| {
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Epic with {epic_id} not found"));
+            };
+            if epic.name != name {
+                epic.record_change(FieldChange::new(
+                    "name",
+                    epic.name.clone(),
+                    name.to_owned(),
+                    &self.current_user,
+                ));
+                epic.name = name.to_owned();
+            }
+            Ok(())
+        })
+    }
+
+    pub fn update_epic_description(&self, epic_id: u32, description: &str) -> Result<()> {
+        let description = description.trim();
+        self.update("update_epic_description", |data| {
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Epic with {epic_id} not found"));
+            };
+            if epic.description != description {
+                let change = FieldChange::new(
+                    "description",
+                    epic.description.clone(),
+                    description.to_owned(),
+                    &self.current_user,
+                );
+                epic.record_change(change);
+                epic.description = description.to_owned();
+            }
+            Ok(())
+        })
     }
 
     pub fn update_epic_status(&self, epic_id: u32, status: Status) -> Result<()> {
-        let mut data = self
+        self.update("update_epic_status", |data| {
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Epic with {epic_id} not found"));
+            };
+            if epic.status != status {
+                let change = FieldChange::new(
+                    "status",
+                    epic.status.to_string(),
+                    status.to_string(),
+                    &self.current_user,
+                );
+                epic.record_change(change);
+                epic.status = status;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn update_story_name(&self, story_id: u32, name: &str) -> Result<()> {
+        let name = item_name(name)?;
+        self.update("update_story_name", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            if story.name != name {
+                story.record_change(FieldChange::new(
+                    "name",
+                    story.name.clone(),
+                    name.to_owned(),
+                    &self.current_user,
+                ));
+                story.name = name.to_owned();
+            }
+            Ok(())
+        })
+    }
+
+    pub fn update_story_description(&self, story_id: u32, description: &str) -> Result<()> {
+        let description = description.trim();
+        self.update("update_story_description", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            if story.description != description {
+                let change = FieldChange::new(
+                    "description",
+                    story.description.clone(),
+                    description.to_owned(),
+                    &self.current_user,
+                );
+                story.record_change(change);
+                story.description = description.to_owned();
+            }
+            Ok(())
+        })
+    }
+
+    pub fn update_story_status(&self, story_id: u32, status: Status) -> Result<()> {
+        self.update("update_story_status", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            if story.status != status {
+                let change = FieldChange::new(
+                    "status",
+                    story.status.to_string(),
+                    status.to_string(),
+                    &self.current_user,
+                );
+                story.record_change(change);
+                story.status = status;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn update_epic_priority(&self, epic_id: u32, priority: Priority) -> Result<()> {
+        self.update("update_epic_priority", |data| {
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Epic with {epic_id} not found"));
+            };
+            if epic.priority != priority {
+                let change = FieldChange::new(
+                    "priority",
+                    epic.priority.to_string(),
+                    priority.to_string(),
+                    &self.current_user,
+                );
+                epic.record_change(change);
+                epic.priority = priority;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn update_story_priority(&self, story_id: u32, priority: Priority) -> Result<()> {
+        self.update("update_story_priority", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            if story.priority != priority {
+                let change = FieldChange::new(
+                    "priority",
+                    story.priority.to_string(),
+                    priority.to_string(),
+                    &self.current_user,
+                );
+                story.record_change(change);
+                story.priority = priority;
+            }
+            Ok(())
+        })
+    }
+
+    /// Adds someone to the board's users, or changes their name.
+    pub fn add_user(&self, username: &str, user: User) -> Result<()> {
+        User::validate_username(username)?;
+        self.update("add_user", |data| {
+            data.users.insert(username.to_owned(), user);
+            Ok(())
+        })
+    }
+
+    /// Removes someone from the board's users and unassigns everything they had.
+    pub fn remove_user(&self, username: &str) -> Result<()> {
+        self.update("remove_user", |data| {
+            if data.users.remove(username).is_none() {
+                return Err(anyhow!("Unknown user {username}"));
+            }
+            for epic in data.epics.values_mut() {
+                if epic.owner.as_deref() == Some(username) {
+                    epic.record_change(user_change(
+                        "owner",
+                        &epic.owner,
+                        &None,
+                        &self.current_user,
+                    ));
+                    epic.owner = None;
+                }
+            }
+            for story in data.stories.values_mut() {
+                if story.assignee.as_deref() == Some(username) {
+                    story.record_change(user_change(
+                        "assignee",
+                        &story.assignee,
+                        &None,
+                        &self.current_user,
+                    ));
+                    story.assignee = None;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// `None` removes the owner.
+    pub fn update_epic_owner(&self, epic_id: u32, owner: Option<String>) -> Result<()> {
+        self.update("update_epic_owner", |data| {
+            check_user(data, owner.as_deref())?;
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Epic with {epic_id} not found"));
+            };
+            if epic.owner != owner {
+                epic.record_change(user_change(
+                    "owner",
+                    &epic.owner,
+                    &owner,
+                    &self.current_user,
+                ));
+                epic.owner = owner;
+            }
+            Ok(())
+        })
+    }
+
+    /// `None` unassigns the story.
+    pub fn assign_story(&self, story_id: u32, assignee: Option<String>) -> Result<()> {
+        self.update("assign_story", |data| {
+            check_user(data, assignee.as_deref())?;
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            if story.assignee != assignee {
+                story.record_change(user_change(
+                    "assignee",
+                    &story.assignee,
+                    &assignee,
+                    &self.current_user,
+                ));
+                story.assignee = assignee;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn add_epic_label(&self, epic_id: u32, label: &str) -> Result<()> {
+        validate_label(label)?;
+        self.update("add_epic_label", |data| {
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Epic with {epic_id} not found"));
+            };
+            if let Some(change) = label_change(
+                &mut epic.labels,
+                |labels| labels.insert(label.to_owned()),
+                &self.current_user,
+            ) {
+                epic.record_change(change);
+            }
+            Ok(())
+        })
+    }
+
+    pub fn remove_epic_label(&self, epic_id: u32, label: &str) -> Result<()> {
+        self.update("remove_epic_label", |data| {
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Epic with {epic_id} not found"));
+            };
+            let Some(change) = label_change(
+                &mut epic.labels,
+                |labels| labels.remove(label),
+                &self.current_user,
+            ) else {
+                return Err(anyhow!("Epic {epic_id} has no label {label}"));
+            };
+            epic.record_change(change);
+            Ok(())
+        })
+    }
+
+    pub fn add_story_label(&self, story_id: u32, label: &str) -> Result<()> {
+        validate_label(label)?;
+        self.update("add_story_label", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            if let Some(change) = label_change(
+                &mut story.labels,
+                |labels| labels.insert(label.to_owned()),
+                &self.current_user,
+            ) {
+                story.record_change(change);
+            }
+            Ok(())
+        })
+    }
+
+    pub fn remove_story_label(&self, story_id: u32, label: &str) -> Result<()> {
+        self.update("remove_story_label", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            let Some(change) = label_change(
+                &mut story.labels,
+                |labels| labels.remove(label),
+                &self.current_user,
+            ) else {
+                return Err(anyhow!("Story {story_id} has no label {label}"));
+            };
+            story.record_change(change);
+            Ok(())
+        })
+    }
+
+    /// `None` removes the due date.
+    pub fn update_epic_due_date(&self, epic_id: u32, due_date: Option<NaiveDate>) -> Result<()> {
+        self.update("update_epic_due_date", |data| {
+            let Some(epic) = data.epics.get_mut(&epic_id) else {
+                return Err(anyhow!("Epic with {epic_id} not found"));
+            };
+            if epic.due_date != due_date {
+                epic.record_change(due_date_change(epic.due_date, due_date, &self.current_user));
+                epic.due_date = due_date;
+            }
+            Ok(())
+        })
+    }
+
+    /// `None` removes the due date.
+    pub fn update_story_due_date(&self, story_id: u32, due_date: Option<NaiveDate>) -> Result<()> {
+        self.update("update_story_due_date", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            if story.due_date != due_date {
+                story.record_change(due_date_change(
+                    story.due_date,
+                    due_date,
+                    &self.current_user,
+                ));
+                story.due_date = due_date;
+            }
+            Ok(())
+        })
+    }
+
+    /// Appends a comment by the current user to a story, returning the comment's id.
+    pub fn add_comment(&self, story_id: u32, body: &str) -> Result<u32> {
+        let body = comment_body(body)?;
+        self.update("add_comment", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            let id = story
+                .comments
+                .iter()
+                .map(|comment| comment.id)
+                .max()
+                .unwrap_or(0)
+                + 1;
+            story.comments.push(Comment {
+                id,
+                author: self.current_user.clone(),
+                created_at: Utc::now(),
+                edited_at: None,
+                body,
+            });
+            Ok(id)
+        })
+    }
+
+    /// Only the author of a comment can edit it.
+    pub fn edit_comment(&self, story_id: u32, comment_id: u32, body: &str) -> Result<()> {
+        let body = comment_body(body)?;
+        self.update("edit_comment", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            let index = own_comment(&story.comments, comment_id, &self.current_user)?;
+            let comment = &mut story.comments[index];
+            if comment.body != body {
+                comment.body = body;
+                comment.edited_at = Some(Utc::now());
+            }
+            Ok(())
+        })
+    }
+
+    /// Only the author of a comment can delete it.
+    pub fn delete_comment(&self, story_id: u32, comment_id: u32) -> Result<()> {
+        self.update("delete_comment", |data| {
+            let Some(story) = data.stories.get_mut(&story_id) else {
+                return Err(anyhow!("Story with {story_id} not found"));
+            };
+            let index = own_comment(&story.comments, comment_id, &self.current_user)?;
+            story.comments.remove(index);
+            Ok(())
+        })
+    }
+
+    /// Replaces the whole board with `db_state`, provided nobody wrote to it since
+    /// `expected_revision`. Returns the state as stored, with its new revision.
+    pub fn restore(&self, mut db_state: DBState, expected_revision: u64) -> Result<DBState> {
+        let _lock = self.database.lock()?;
+
+        let current = self
             .database
             .read()
-            .with_context(|| format!("Failed to read database on update_epic_status"))?;
+            .context("Failed to read database on restore")?;
+        if current.revision != expected_revision {
+            return Err(ConflictError {
+                expected_revision,
+                found_revision: current.revision,
+            }
+            .into());
+        }
 
-        let Some(epic) = data.epics.get_mut(&epic_id) else {
-            return Err(anyhow!("Epic with {epic_id} not found"))
-        };
-        epic.status = status;
+        // Never hand out an id twice, even if the restored state is older.
+        db_state.last_item_id = db_state.last_item_id.max(current.last_item_id);
+        db_state.revision = current.revision + 1;
 
         self.database
-            .write(&data)
-            .with_context(|| format!("Failed to update epic status on {epic_id}"))
+            .write(&db_state)
+            .context("Failed to write database on restore")?;
+        self.observed_revision.set(Some(db_state.revision));
+        Ok(db_state)
     }
 
-    pub fn update_story_status(&self, story_id: u32, status: Status) -> Result<()> {
+    pub fn take_last_write(&self) -> Option<(DBState, DBState)> {
+        self.last_write.borrow_mut().take()
+    }
+
+    /// Runs a read-modify-write cycle while holding the database lock, so no other
+    /// process can write in between our read and our write.
+    fn update<T>(
+        &self,
+        operation: &str,
+        change: impl FnOnce(&mut DBState) -> Result<T>,
+    ) -> Result<T> {
+        let _lock = self.database.lock()?;
+
         let mut data = self
             .database
             .read()
-            .with_context(|| format!("Failed to read database on update_story_status"))?;
+            .with_context(|| format!("Failed to read database on {operation}"))?;
+
+        if let Some(expected_revision) = self.observed_revision.get() {
+            if expected_revision != data.revision {
+                return Err(ConflictError {
+                    expected_revision,
+                    found_revision: data.revision,
+                }
+                .into());
+            }
+        }
 
-        let Some(story) = data.stories.get_mut(&story_id) else {
-            return Err(anyhow!("Story with {story_id} not found"));
-        };
-        story.status = status;
+        let before = data.clone();
+        let result = change(&mut data)?;
+        data.trash
+            .purge_deleted_before(Utc::now() - self.trash_retention);
+        data.revision += 1;
 
         self.database
             .write(&data)
-            .with_context(|| format!("Failed to update story status on {story_id}"))
+            .with_context(|| format!("Failed to write database on {operation}"))?;
+        self.observed_revision.set(Some(data.revision));
+        *self.last_write.borrow_mut() = Some((before, data));
+        Ok(result)
+    }
+}
+
+/// Only users of the board can own or be assigned items.
+fn check_user(data: &DBState, username: Option<&str>) -> Result<()> {
+    match username {
+        Some(username) if !data.users.contains_key(username) => Err(anyhow!(
+            "Unknown user {username}, add them to the board's users first"
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn user_change(
+    field: &str,
+    from: &Option<String>,
+    to: &Option<String>,
+    author: &str,
+) -> FieldChange {
+    let name = |user: &Option<String>| user.clone().unwrap_or_else(|| "nobody".to_owned());
+    FieldChange::new(field, name(from), name(to), author)
+}
+
+/// Applies `update` to `labels`, returning the history entry when it changed them.
+fn label_change(
+    labels: &mut BTreeSet<String>,
+    update: impl FnOnce(&mut BTreeSet<String>) -> bool,
+    author: &str,
+) -> Option<FieldChange> {
+    let names = |labels: &BTreeSet<String>| match labels.is_empty() {
+        true => "none".to_owned(),
+        false => labels.iter().cloned().collect::<Vec<_>>().join(", "),
+    };
+    let from = names(labels);
+    update(labels).then(|| FieldChange::new("labels", from, names(labels), author))
+}
+
+fn comment_body(body: &str) -> Result<String> {
+    match body.trim() {
+        "" => Err(anyhow!("A comment can't be empty")),
+        body => Ok(body.to_owned()),
+    }
+}
+
+/// The index of comment `comment_id`, provided `username` wrote it.
+fn own_comment(comments: &[Comment], comment_id: u32, username: &str) -> Result<usize> {
+    let index = comments
+        .iter()
+        .position(|comment| comment.id == comment_id)
+        .ok_or_else(|| anyhow!("Comment #{comment_id} not found"))?;
+    if comments[index].author != username {
+        return Err(anyhow!(
+            "Comment #{comment_id} is by {}, you can only change your own comments",
+            comments[index].author
+        ));
+    }
+    Ok(index)
+}
+
+fn item_name(name: &str) -> Result<&str> {
+    match name.trim() {
+        "" => Err(anyhow!("A name can't be empty")),
+        name => Ok(name),
+    }
+}
+
+fn due_date_change(from: Option<NaiveDate>, to: Option<NaiveDate>, author: &str) -> FieldChange {
+    let name = |date: Option<NaiveDate>| date.map_or_else(|| "none".to_owned(), |d| d.to_string());
+    FieldChange::new("due date", name(from), name(to), author)
+}
+
+/// Returned by mutations when the database changed since the caller last read it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConflictError {
+    pub expected_revision: u64,
+    pub found_revision: u64,
+}
+
+impl Display for ConflictError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The board was changed by someone else (revision {} instead of {})",
+            self.found_revision, self.expected_revision
+        )
     }
 }
 
+impl std::error::Error for ConflictError {}
+
 pub trait Database {
     fn read(&self) -> Result<DBState>;
     fn write(&self, db_state: &DBState) -> Result<()>;
+
+    /// Who the following writes are made by, for backends that record it.
+    fn set_author(&self, _author: &str) {}
+
+    /// Takes an exclusive lock for a read-modify-write cycle, released when the
+    /// returned guard is dropped. Backends that can't be shared don't need one.
+    fn lock(&self) -> Result<DatabaseLock> {
+        Ok(DatabaseLock { file: None })
+    }
+}
+
+/// Advisory lock on a `<database>.lock` file next to the database.
+pub struct DatabaseLock {
+    file: Option<fs::File>,
+}
+
+impl DatabaseLock {
+    const RETRIES: u32 = 20;
+    const RETRY_DELAY: Duration = Duration::from_millis(50);
+
+    fn acquire(database_path: &str) -> Result<Self> {
+        let lock_path = format!("{database_path}.lock");
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open lock file {lock_path}"))?;
+
+        for _ in 0..Self::RETRIES {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { file: Some(file) }),
+                Err(TryLockError::WouldBlock) => thread::sleep(Self::RETRY_DELAY),
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).with_context(|| format!("Failed to lock {lock_path}"))
+                }
+            }
+        }
+
+        Err(anyhow!(
+            "Database {database_path} is locked by another process, try again in a moment"
+        ))
+    }
+}
+
+impl Drop for DatabaseLock {
+    fn drop(&mut self) {
+        if let Some(file) = &self.file {
+            file.unlock().ok();
+        }
+    }
 }
 
 struct JSONFileDatabase {
     pub file_path: String,
 }
 
+impl JSONFileDatabase {
+    /// Keeps a copy of the file as it was before its first migration from `version`.
+    fn backup(&self, version: u64) -> Result<()> {
+        let backup_path = format!("{}.v{version}.bak", self.file_path);
+        if !Path::new(&backup_path).exists() {
+            fs::copy(&self.file_path, &backup_path)
+                .with_context(|| format!("Failed to back up database to {backup_path}"))?;
+        }
+        Ok(())
+    }
+}
+
 impl Database for JSONFileDatabase {
+    /// Older documents are upgraded in memory, the upgraded version is persisted by the
+    /// next write.
     fn read(&self) -> Result<DBState> {
-        let file =
-            fs::File::open(&self.file_path).with_context(|| format!("Error while opening file"))?;
+        let file = fs::File::open(&self.file_path).context("Error while opening file")?;
+
+        let mut document = serde_json::from_reader(file)?;
+        let version = migrate(&mut document)?;
+        if version < SCHEMA_VERSION {
+            self.backup(version)?;
+        }
 
-        let db_state = serde_json::from_reader(file)?;
+        let db_state = serde_json::from_value(document)?;
         Ok(db_state)
     }
 
     fn write(&self, db_state: &DBState) -> Result<()> {
-        fs::write(&self.file_path, &serde_json::to_vec(db_state)?)?;
-        Ok(())
+        let mut document = serde_json::to_value(db_state)?;
+        document["schema_version"] = SCHEMA_VERSION.into();
+        let contents = serde_json::to_vec(&document)?;
+        write_atomically(Path::new(&self.file_path), &contents)
+            .with_context(|| format!("Failed to write {}", self.file_path))
+    }
+
+    fn lock(&self) -> Result<DatabaseLock> {
+        DatabaseLock::acquire(&self.file_path)
     }
 }
 
+/// Version of the document layout written by `JSONFileDatabase`. Bump it together with a
+/// new entry in `MIGRATIONS` whenever `DBState` or the models inside it change shape.
+pub const SCHEMA_VERSION: u64 = 8;
+
+type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// `MIGRATIONS[n]` upgrades a version `n` document to version `n + 1`.
+const MIGRATIONS: [Migration; SCHEMA_VERSION as usize] = [
+    migrate_v0_add_revision,
+    migrate_v1_add_history,
+    migrate_v2_add_trash,
+    migrate_v3_add_priority,
+    migrate_v4_add_users,
+    migrate_v5_add_labels,
+    migrate_v6_add_due_dates,
+    migrate_v7_add_comments,
+];
+
+/// Upgrades `document` to `SCHEMA_VERSION` one step at a time, returning the version it
+/// was stored with. Documents without a `schema_version` predate versioning (version 0).
+fn migrate(document: &mut Value) -> Result<u64> {
+    let document = document
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("Database document is not a json object"))?;
+
+    let version = match document.get("schema_version") {
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| anyhow!("Invalid schema_version: {version}"))?,
+        None => 0,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(anyhow!(
+            "Database schema version {version} is newer than the supported version {SCHEMA_VERSION}, please upgrade jira-cli"
+        ));
+    }
+
+    for (from_version, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(document)
+            .with_context(|| format!("Failed to migrate database from version {from_version}"))?;
+    }
+    document.insert("schema_version".to_owned(), SCHEMA_VERSION.into());
+
+    Ok(version)
+}
+
+fn migrate_v0_add_revision(document: &mut Map<String, Value>) -> Result<()> {
+    document.entry("revision").or_insert(Value::from(0));
+    Ok(())
+}
+
+fn migrate_v1_add_history(document: &mut Map<String, Value>) -> Result<()> {
+    for table in ["epics", "stories"] {
+        let Some(Value::Object(items)) = document.get_mut(table) else {
+            continue;
+        };
+        for item in items.values_mut().filter_map(Value::as_object_mut) {
+            for field in ["created_at", "created_by", "updated_at"] {
+                item.entry(field).or_insert(Value::Null);
+            }
+            item.entry("history").or_insert(Value::Array(Vec::new()));
+        }
+    }
+    Ok(())
+}
+
+fn migrate_v2_add_trash(document: &mut Map<String, Value>) -> Result<()> {
+    document
+        .entry("trash")
+        .or_insert(serde_json::json!({ "epics": {}, "stories": {} }));
+    Ok(())
+}
+
+fn migrate_v3_add_priority(document: &mut Map<String, Value>) -> Result<()> {
+    for table in ["epics", "stories"] {
+        update_items(document, table, |item| {
+            item.entry("priority").or_insert(Value::from("Medium"));
+        });
+    }
+    Ok(())
+}
+
+fn migrate_v4_add_users(document: &mut Map<String, Value>) -> Result<()> {
+    document.entry("users").or_insert(Value::Object(Map::new()));
+    update_items(document, "epics", |epic| {
+        epic.entry("owner").or_insert(Value::Null);
+    });
+    update_items(document, "stories", |story| {
+        story.entry("assignee").or_insert(Value::Null);
+    });
+    Ok(())
+}
+
+fn migrate_v5_add_labels(document: &mut Map<String, Value>) -> Result<()> {
+    for table in ["epics", "stories"] {
+        update_items(document, table, |item| {
+            item.entry("labels").or_insert(Value::Array(Vec::new()));
+        });
+    }
+    Ok(())
+}
+
+fn migrate_v6_add_due_dates(document: &mut Map<String, Value>) -> Result<()> {
+    for table in ["epics", "stories"] {
+        update_items(document, table, |item| {
+            item.entry("due_date").or_insert(Value::Null);
+        });
+    }
+    Ok(())
+}
+
+fn migrate_v7_add_comments(document: &mut Map<String, Value>) -> Result<()> {
+    update_items(document, "stories", |story| {
+        story.entry("comments").or_insert(Value::Array(Vec::new()));
+    });
+    Ok(())
+}
+
+/// Applies `update` to the live and the trashed items of `table`.
+fn update_items(
+    document: &mut Map<String, Value>,
+    table: &str,
+    update: impl Fn(&mut Map<String, Value>),
+) {
+    if let Some(Value::Object(items)) = document.get_mut(table) {
+        items
+            .values_mut()
+            .filter_map(Value::as_object_mut)
+            .for_each(&update);
+    }
+
+    let trashed_field = if table == "epics" { "epic" } else { "story" };
+    let trashed = document
+        .get_mut("trash")
+        .and_then(|trash| trash.get_mut(table))
+        .and_then(Value::as_object_mut);
+    for trashed in trashed.into_iter().flat_map(|trashed| trashed.values_mut()) {
+        if let Some(item) = trashed
+            .get_mut(trashed_field)
+            .and_then(Value::as_object_mut)
+        {
+            update(item);
+        }
+    }
+}
+
+/// Writes to a temp file in the same directory, fsyncs it and renames it over `path`,
+/// so a crash mid-write never leaves a truncated file behind.
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(format!(".tmp-{}", process::id()));
+
+    let result = fs::File::create(&tmp_path).and_then(|mut tmp_file| {
+        tmp_file.write_all(contents)?;
+        tmp_file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    });
+    if result.is_err() {
+        fs::remove_file(&tmp_path).ok();
+    }
+    result?;
+
+    // Persist the rename itself; not every platform lets us open a directory.
+    if let Some(directory) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if let Ok(directory) = fs::File::open(directory) {
+            directory.sync_all().ok();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
 pub mod test_utils {
     use super::*;
-    use std::{cell::RefCell, collections::HashMap};
 
     pub struct MockDB {
         last_written_state: RefCell<DBState>,
@@ -167,11 +1183,7 @@ pub mod test_utils {
     impl MockDB {
         pub fn new() -> Self {
             Self {
-                last_written_state: RefCell::new(DBState {
-                    last_item_id: 0,
-                    epics: HashMap::new(),
-                    stories: HashMap::new(),
-                }),
+                last_written_state: RefCell::new(DBState::default()),
             }
         }
     }
@@ -197,9 +1209,7 @@ mod tests {
 
     #[test]
     fn create_epic_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("Epic 1".to_owned(), "Epic 1 description".to_owned());
         let result = db.create_epic(epic.clone());
 
@@ -212,14 +1222,13 @@ mod tests {
 
         assert_eq!(id, expected_id);
         assert_eq!(db_state.last_item_id, expected_id);
-        assert_eq!(db_state.epics.get(&id), Some(&epic));
+        let created_by = Some(db.current_user().to_owned());
+        assert_eq!(db_state.epics.get(&id), Some(&Epic { created_by, ..epic }));
     }
 
     #[test]
     fn create_story_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let non_existent_epic_id = 999;
         let story = Story::new("".to_owned(), "".to_owned());
         let result = db.create_story(story, non_existent_epic_id);
@@ -228,9 +1237,7 @@ mod tests {
 
     #[test]
     fn create_story_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("Epic_1".to_owned(), "Custom_epic".to_owned());
         let story = Story::new("story_1".to_owned(), "description_1".to_owned());
 
@@ -246,7 +1253,14 @@ mod tests {
 
         assert!(created_story_id.is_ok());
         assert_eq!(db_state.last_item_id, expected_story_id);
-        assert_eq!(db_state.stories.get(&expected_story_id), Some(&story));
+        let created_by = Some(db.current_user().to_owned());
+        assert_eq!(
+            db_state.stories.get(&expected_story_id),
+            Some(&Story {
+                created_by,
+                ..story
+            })
+        );
         assert!(db_state
             .epics
             .get(&expected_epic_id)
@@ -257,9 +1271,7 @@ mod tests {
 
     #[test]
     fn delete_epic_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
 
         let inesistent_epic_id = 999;
         let result = db.delete_epic(inesistent_epic_id);
@@ -269,9 +1281,7 @@ mod tests {
 
     #[test]
     fn delete_epic_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
 
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
@@ -286,16 +1296,20 @@ mod tests {
         let expected_last_item_id = 2;
 
         assert_eq!(db_state.last_item_id, expected_last_item_id);
-        assert!(db_state.epics.get(&created_epic_id).is_none());
-        assert!(db_state.stories.get(&created_story_id).is_none());
+        assert!(!db_state.epics.contains_key(&created_epic_id));
+        assert!(!db_state.stories.contains_key(&created_story_id));
         assert!(result.is_ok());
+
+        assert!(db_state.trash.epics.contains_key(&created_epic_id));
+        assert_eq!(
+            db_state.trash.stories[&created_story_id].epic_id,
+            created_epic_id
+        );
     }
 
     #[test]
     fn delete_story_should_error_if_invalid_epic_or_invalid_story() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
 
         let invalid_epic_id = 999;
         let invalid_story_id = 999;
@@ -310,9 +1324,7 @@ mod tests {
 
     #[test]
     fn delete_story_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
 
         let epic = Epic::new("".to_owned(), "".to_owned());
         let epic_id = db.create_epic(epic).unwrap();
@@ -321,7 +1333,7 @@ mod tests {
         let story_id = db.create_story(story, epic_id).unwrap();
 
         let data = db.read().unwrap();
-        assert!(data.stories.get(&story_id).is_some());
+        assert!(data.stories.contains_key(&story_id));
         assert!(data
             .epics
             .get(&epic_id)
@@ -335,13 +1347,103 @@ mod tests {
         let data = db.read().unwrap();
         assert!(data.epics.get(&epic_id).unwrap().stories.is_empty());
         assert_eq!(data.stories.get(&story_id), None);
+        assert!(data.trash.stories.contains_key(&story_id));
+    }
+
+    #[test]
+    fn restore_epic_should_bring_back_its_stories() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let before = db.read().unwrap();
+
+        db.delete_epic(epic_id).unwrap();
+        db.restore_epic(epic_id).unwrap();
+
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.epics, before.epics);
+        assert_eq!(db_state.stories, before.stories);
+        assert!(db_state.trash.is_empty());
+        assert_eq!(
+            db.restore_story(story_id).unwrap_err().to_string(),
+            format!("Story {story_id} is not in the trash")
+        );
+    }
+
+    #[test]
+    fn restore_story_should_relink_it_to_its_epic() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let other_story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        db.delete_story(epic_id, story_id).unwrap();
+        db.delete_epic(epic_id).unwrap();
+        assert!(db.restore_story(story_id).is_err());
+
+        db.restore_epic(epic_id).unwrap();
+        assert_eq!(
+            db.read().unwrap().epics[&epic_id].stories,
+            vec![other_story_id]
+        );
+
+        db.restore_story(story_id).unwrap();
+        let db_state = db.read().unwrap();
+        assert_eq!(
+            db_state.epics[&epic_id].stories,
+            vec![other_story_id, story_id]
+        );
+        assert!(db_state.stories.contains_key(&story_id));
+    }
+
+    #[test]
+    fn purge_should_remove_items_for_good() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.purge_epic(epic_id).is_err());
+        db.delete_epic(epic_id).unwrap();
+        db.purge_epic(epic_id).unwrap();
+
+        let db_state = db.read().unwrap();
+        assert!(db_state.trash.is_empty());
+        assert!(db.purge_story(story_id).is_err());
+        assert!(db.restore_epic(epic_id).is_err());
+    }
+
+    #[test]
+    fn mutations_should_purge_expired_trash() {
+        let mut db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        db.delete_epic(epic_id).unwrap();
+        assert!(db.read().unwrap().trash.epics.contains_key(&epic_id));
+
+        db.set_trash_retention(TimeDelta::zero());
+        db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        assert!(db.read().unwrap().trash.is_empty());
     }
 
     #[test]
     fn update_epic_status_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
 
         let inesistent_epic_id = 999;
         let result = db.update_epic_status(inesistent_epic_id, Status::InProgress);
@@ -350,9 +1452,7 @@ mod tests {
 
     #[test]
     fn update_epic_status_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
 
         let epic = Epic::new("".to_owned(), "".to_owned());
         let epic_id = db.create_epic(epic.clone()).unwrap();
@@ -370,9 +1470,7 @@ mod tests {
 
     #[test]
     fn update_story_status_should_error_if_invalid_story_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let inesistent_story_status = 999;
 
         let result = db.update_story_status(inesistent_story_status, Status::InProgress);
@@ -381,9 +1479,7 @@ mod tests {
 
     #[test]
     fn update_story_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
@@ -400,6 +1496,387 @@ mod tests {
         );
     }
 
+    #[test]
+    fn update_status_should_record_history() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        db.update_story_status(story_id, Status::Closed).unwrap();
+
+        let db_state = db.read().unwrap();
+        let story = &db_state.stories[&story_id];
+        let changes = story
+            .history
+            .iter()
+            .map(|change| {
+                (
+                    change.field.as_str(),
+                    change.from.as_str(),
+                    change.to.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                ("status", "Open", "In Progress"),
+                ("status", "In Progress", "Closed")
+            ]
+        );
+        assert_eq!(story.updated_at, Some(story.history[1].at));
+        assert!(story.created_at.is_some());
+        assert!(db_state.epics[&epic_id].history.is_empty());
+    }
+
+    #[test]
+    fn update_priority_should_record_history() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        db.update_epic_priority(epic_id, Priority::Medium).unwrap();
+        db.update_epic_priority(epic_id, Priority::Lowest).unwrap();
+        assert!(db.update_story_priority(999, Priority::High).is_err());
+
+        let db_state = db.read().unwrap();
+        let epic = &db_state.epics[&epic_id];
+        assert_eq!(epic.priority, Priority::Lowest);
+        assert_eq!(epic.history.len(), 1);
+        assert_eq!(
+            (epic.history[0].from.as_str(), epic.history[0].to.as_str()),
+            ("Medium", "Lowest")
+        );
+    }
+
+    #[test]
+    fn assignments_should_only_accept_known_users() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.assign_story(story_id, Some("alice".to_owned())).is_err());
+        assert!(db.add_user("alice smith", User::default()).is_err());
+        db.add_user("alice", User::default()).unwrap();
+        db.assign_story(story_id, Some("alice".to_owned())).unwrap();
+        db.update_epic_owner(epic_id, Some("alice".to_owned()))
+            .unwrap();
+        db.assign_story(story_id, None).unwrap();
+
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.stories[&story_id].assignee, None);
+        assert_eq!(db_state.epics[&epic_id].owner.as_deref(), Some("alice"));
+        let changes = db_state.stories[&story_id]
+            .history
+            .iter()
+            .map(|change| (change.from.as_str(), change.to.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(changes, vec![("nobody", "alice"), ("alice", "nobody")]);
+
+        db.remove_user("alice").unwrap();
+        assert_eq!(db.read().unwrap().epics[&epic_id].owner, None);
+        assert!(db.remove_user("alice").is_err());
+    }
+
+    #[test]
+    fn labels_should_be_added_and_removed_with_history() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.add_story_label(story_id, "").is_err());
+        db.add_story_label(story_id, "backend").unwrap();
+        db.add_story_label(story_id, "api").unwrap();
+        db.add_story_label(story_id, "api").unwrap();
+        db.remove_story_label(story_id, "backend").unwrap();
+        assert!(db.remove_story_label(story_id, "backend").is_err());
+        db.add_epic_label(epic_id, "customer-x").unwrap();
+
+        let db_state = db.read().unwrap();
+        let changes = db_state.stories[&story_id]
+            .history
+            .iter()
+            .map(|change| (change.from.as_str(), change.to.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                ("none", "backend"),
+                ("backend", "api, backend"),
+                ("api, backend", "api")
+            ]
+        );
+        assert!(db_state.epics[&epic_id].labels.contains("customer-x"));
+        assert!(db.remove_epic_label(epic_id + 10, "customer-x").is_err());
+    }
+
+    #[test]
+    fn due_dates_should_be_set_and_removed_with_history() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let due_date = NaiveDate::from_ymd_opt(2023, 5, 19);
+
+        db.update_story_due_date(story_id, due_date).unwrap();
+        db.update_story_due_date(story_id, due_date).unwrap();
+        db.update_story_due_date(story_id, None).unwrap();
+        db.update_epic_due_date(epic_id, due_date).unwrap();
+        assert!(db.update_epic_due_date(epic_id + 10, None).is_err());
+
+        let db_state = db.read().unwrap();
+        let changes = db_state.stories[&story_id]
+            .history
+            .iter()
+            .map(|change| (change.from.as_str(), change.to.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![("none", "2023-05-19"), ("2023-05-19", "none")]
+        );
+        assert_eq!(db_state.epics[&epic_id].due_date, due_date);
+    }
+
+    #[test]
+    fn move_story_should_relink_the_story() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let from = db
+            .create_epic(Epic::new("Payments".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), from)
+            .unwrap();
+        let other_story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), from)
+            .unwrap();
+        let to = db
+            .create_epic(Epic::new("Billing".to_owned(), "".to_owned()))
+            .unwrap();
+
+        assert!(db.move_story(story_id, 999).is_err());
+        assert!(db.move_story(999, to).is_err());
+        db.move_story(story_id, from).unwrap();
+        db.move_story(story_id, to).unwrap();
+
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.epics[&from].stories, vec![other_story_id]);
+        assert_eq!(db_state.epics[&to].stories, vec![story_id]);
+        let change = db_state.stories[&story_id].history.last().unwrap();
+        assert_eq!(
+            (
+                change.field.as_str(),
+                change.from.as_str(),
+                change.to.as_str()
+            ),
+            ("epic", "Payments", "Billing")
+        );
+    }
+
+    #[test]
+    fn rank_story_should_reorder_the_epics_stories() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let [a, b, c, d] = [(); 4].map(|_| {
+            db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap()
+        });
+        let other_epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let other = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), other_epic_id)
+            .unwrap();
+        let ranked = || db.read().unwrap().epics[&epic_id].stories.clone();
+
+        db.rank_story(d, StoryRank::Before(b)).unwrap();
+        assert_eq!(ranked(), vec![a, d, b, c]);
+        db.rank_story(a, StoryRank::Before(c)).unwrap();
+        assert_eq!(ranked(), vec![d, b, a, c]);
+        db.rank_story(c, StoryRank::Top).unwrap();
+        assert_eq!(ranked(), vec![c, d, b, a]);
+        db.rank_story(c, StoryRank::Up).unwrap();
+        db.rank_story(a, StoryRank::Down).unwrap();
+        assert_eq!(ranked(), vec![c, d, b, a]);
+        db.rank_story(b, StoryRank::Up).unwrap();
+        db.rank_story(c, StoryRank::Down).unwrap();
+        assert_eq!(ranked(), vec![b, c, d, a]);
+
+        assert!(db.rank_story(a, StoryRank::Before(other)).is_err());
+        assert!(db.rank_story(999, StoryRank::Top).is_err());
+    }
+
+    #[test]
+    fn update_name_and_description_should_record_changes() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("Paymnts".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("Checkout".to_owned(), "Old".to_owned()), epic_id)
+            .unwrap();
+
+        db.update_epic_name(epic_id, " Payments ").unwrap();
+        db.update_epic_description(epic_id, "Card payments")
+            .unwrap();
+        db.update_story_name(story_id, "Checkout").unwrap();
+        db.update_story_description(story_id, "").unwrap();
+        assert!(db.update_story_name(story_id, "  ").is_err());
+        assert!(db.update_epic_name(999, "Name").is_err());
+
+        let db_state = db.read().unwrap();
+        let epic = &db_state.epics[&epic_id];
+        assert_eq!(epic.name, "Payments");
+        assert_eq!(epic.description, "Card payments");
+        let fields = epic
+            .history
+            .iter()
+            .map(|change| change.field.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["name", "description"]);
+
+        let story = &db_state.stories[&story_id];
+        assert_eq!(story.name, "Checkout");
+        assert_eq!(story.description, "");
+        assert_eq!(story.history.len(), 1);
+    }
+
+    #[test]
+    fn comments_should_only_be_changed_by_their_author() {
+        let mut db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        db.set_current_user("alice".to_owned());
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.add_comment(story_id, "  ").is_err());
+        assert_eq!(db.add_comment(story_id, " First ").unwrap(), 1);
+        assert_eq!(db.add_comment(story_id, "Second").unwrap(), 2);
+        db.edit_comment(story_id, 1, "First, edited").unwrap();
+        db.delete_comment(story_id, 2).unwrap();
+        assert!(db.delete_comment(story_id, 2).is_err());
+
+        db.set_current_user("bob".to_owned());
+        assert!(db.edit_comment(story_id, 1, "Mine now").is_err());
+        assert!(db.delete_comment(story_id, 1).is_err());
+        assert_eq!(db.add_comment(story_id, "Reply").unwrap(), 2);
+
+        let comments = db.read().unwrap().stories[&story_id].comments.clone();
+        let comments = comments
+            .iter()
+            .map(|comment| {
+                let edited = comment.edited_at.is_some();
+                (
+                    comment.id,
+                    comment.author.as_str(),
+                    comment.body.as_str(),
+                    edited,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            comments,
+            vec![
+                (1, "alice", "First, edited", true),
+                (2, "bob", "Reply", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn mutations_should_increment_revision() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        assert_eq!(db.read().unwrap().revision, 0);
+
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        db.update_epic_status(epic_id, Status::Closed).unwrap();
+
+        assert_eq!(db.read().unwrap().revision, 2);
+    }
+
+    #[test]
+    fn mutation_should_fail_with_conflict_on_stale_revision() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        fs::write(
+            tmpfile.path(),
+            r#"{ "schema_version": 8, "revision": 0, "last_item_id": 0, "epics": {}, "stories": {} }"#,
+        )
+        .unwrap();
+        let file_path = tmpfile.path().to_str().unwrap().to_string();
+        let db = JiraDatabase::new(file_path.clone());
+        let teammate_db = JiraDatabase::new(file_path);
+
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        db.read().unwrap();
+        teammate_db
+            .update_epic_status(epic_id, Status::Resolved)
+            .unwrap();
+
+        let error = db.update_epic_status(epic_id, Status::Closed).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<ConflictError>(),
+            Some(&ConflictError {
+                expected_revision: 1,
+                found_revision: 2,
+            })
+        );
+        assert_eq!(db.read().unwrap().epics[&epic_id].status, Status::Resolved);
+
+        assert!(db.update_epic_status(epic_id, Status::Closed).is_ok());
+    }
+
+    #[test]
+    fn open_should_start_an_empty_board_for_each_backend() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for file_name in ["boards/payments.json", "platform.sqlite", "events.log"] {
+            let path = dir.path().join(file_name);
+            let db = JiraDatabase::open(&path).unwrap();
+            assert!(db.read().unwrap().epics.is_empty());
+
+            db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            assert!(path.exists());
+            assert_eq!(
+                JiraDatabase::open(&path)
+                    .unwrap()
+                    .read()
+                    .unwrap()
+                    .epics
+                    .len(),
+                1
+            );
+        }
+    }
+
     mod database {
         use std::collections::HashMap;
         use std::io::Write;
@@ -411,7 +1888,7 @@ mod tests {
             let db = JSONFileDatabase {
                 file_path: "invalid_path".to_string(),
             };
-            assert_eq!(db.read().is_err(), true);
+            assert!(db.read().is_err());
         }
 
         #[test]
@@ -428,12 +1905,12 @@ mod tests {
             };
 
             let result = db.read();
-            assert_eq!(result.is_err(), true);
+            assert!(result.is_err());
         }
 
         #[test]
         fn read_db_should_parse_json_file() {
-            let file_contents = r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#;
+            let file_contents = r#"{ "schema_version": 8, "revision": 0, "last_item_id": 0, "epics": {}, "stories": {} }"#;
             let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
             write!(tmpfile, "{}", file_contents).unwrap();
             let db = JSONFileDatabase {
@@ -453,7 +1930,7 @@ mod tests {
 
         #[test]
         fn write_db_should_word() {
-            let file_contents = r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#;
+            let file_contents = r#"{ "schema_version": 8, "revision": 0, "last_item_id": 0, "epics": {}, "stories": {} }"#;
             let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
             write!(tmpfile, "{}", file_contents).unwrap();
             let db = JSONFileDatabase {
@@ -465,17 +1942,15 @@ mod tests {
             };
 
             let story = Story::new("Story 1".to_owned(), "Description 1".to_owned());
-            let epic = Epic {
-                name: "Epic 1".to_owned(),
-                description: "Description 1".to_owned(),
-                status: Status::Open,
-                stories: vec![2],
-            };
+            let mut epic = Epic::new("Epic 1".to_owned(), "Description 1".to_owned());
+            epic.stories = vec![2];
 
             let db_state = DBState {
+                revision: 1,
                 last_item_id: 1,
                 epics: HashMap::from_iter([(1, epic)]),
                 stories: HashMap::from_iter([(2, story)]),
+                ..Default::default()
             };
 
             let write_result = db.write(&db_state);
@@ -484,5 +1959,185 @@ mod tests {
             let read_result = db.read().unwrap();
             assert_eq!(read_result, db_state);
         }
+
+        #[test]
+        fn read_db_should_migrate_old_schema_and_keep_a_backup() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json");
+            let file_contents = r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#;
+            fs::write(&file_path, file_contents).unwrap();
+            let db = JSONFileDatabase {
+                file_path: file_path.to_str().unwrap().to_string(),
+            };
+
+            let db_state = db.read().unwrap();
+            assert_eq!(db_state, DBState::default());
+
+            let backup = fs::read_to_string(dir.path().join("db.json.v0.bak")).unwrap();
+            assert_eq!(backup, file_contents);
+
+            db.write(&db_state).unwrap();
+            let document: Value =
+                serde_json::from_str(&fs::read_to_string(&file_path).unwrap()).unwrap();
+            assert_eq!(document["schema_version"], SCHEMA_VERSION);
+            assert_eq!(document["revision"], 0);
+        }
+
+        #[test]
+        fn read_db_should_migrate_items_without_history() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json");
+            let file_contents = r#"{ "schema_version": 1, "revision": 3, "last_item_id": 2, "epics": { "1": { "name": "Epic 1", "description": "", "status": "Open", "stories": [2] } }, "stories": { "2": { "name": "Story 1", "description": "", "status": "Closed" } } }"#;
+            fs::write(&file_path, file_contents).unwrap();
+            let db = JSONFileDatabase {
+                file_path: file_path.to_str().unwrap().to_string(),
+            };
+
+            let db_state = db.read().unwrap();
+            assert_eq!(db_state.epics[&1].created_at, None);
+            assert!(db_state.epics[&1].history.is_empty());
+            assert_eq!(db_state.stories[&2].status, Status::Closed);
+            assert!(db_state.stories[&2].history.is_empty());
+            assert!(dir.path().join("db.json.v1.bak").exists());
+        }
+
+        #[test]
+        fn read_db_should_give_items_a_default_priority() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json");
+            let file_contents = r#"{ "schema_version": 3, "revision": 1, "last_item_id": 2, "epics": { "1": { "name": "Epic 1", "description": "", "status": "Open", "stories": [] } }, "stories": {}, "trash": { "epics": {}, "stories": { "2": { "story": { "name": "Story 1", "description": "", "status": "Open" }, "epic_id": 1, "deleted_at": "2024-01-01T00:00:00Z", "deleted_by": "alice" } } } }"#;
+            fs::write(&file_path, file_contents).unwrap();
+            let db = JSONFileDatabase {
+                file_path: file_path.to_str().unwrap().to_string(),
+            };
+
+            let db_state = db.read().unwrap();
+            assert_eq!(db_state.epics[&1].priority, Priority::Medium);
+            assert_eq!(db_state.trash.stories[&2].story.priority, Priority::Medium);
+            assert!(dir.path().join("db.json.v3.bak").exists());
+        }
+
+        #[test]
+        fn read_db_should_add_users_and_empty_assignments() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json");
+            let file_contents = r#"{ "schema_version": 4, "revision": 1, "last_item_id": 2, "epics": { "1": { "name": "Epic 1", "description": "", "status": "Open", "priority": "High", "stories": [2] } }, "stories": { "2": { "name": "Story 1", "description": "", "status": "Open", "priority": "Low" } }, "trash": { "epics": {}, "stories": {} } }"#;
+            fs::write(&file_path, file_contents).unwrap();
+            let db = JSONFileDatabase {
+                file_path: file_path.to_str().unwrap().to_string(),
+            };
+
+            let db_state = db.read().unwrap();
+            assert!(db_state.users.is_empty());
+            assert_eq!(db_state.epics[&1].owner, None);
+            assert_eq!(db_state.stories[&2].assignee, None);
+            assert!(dir.path().join("db.json.v4.bak").exists());
+        }
+
+        #[test]
+        fn read_db_should_give_items_empty_labels() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json");
+            let file_contents = r#"{ "schema_version": 5, "revision": 1, "last_item_id": 2, "epics": { "1": { "name": "Epic 1", "description": "", "status": "Open", "owner": null, "stories": [] } }, "stories": {}, "trash": { "epics": {}, "stories": { "2": { "story": { "name": "Story 1", "description": "", "status": "Open", "assignee": null }, "epic_id": 1, "deleted_at": "2023-05-19T08:00:00Z", "deleted_by": "alice" } } }, "users": {} }"#;
+            fs::write(&file_path, file_contents).unwrap();
+            let db = JSONFileDatabase {
+                file_path: file_path.to_str().unwrap().to_string(),
+            };
+
+            let db_state = db.read().unwrap();
+            assert!(db_state.epics[&1].labels.is_empty());
+            assert!(db_state.trash.stories[&2].story.labels.is_empty());
+            assert!(dir.path().join("db.json.v5.bak").exists());
+        }
+
+        #[test]
+        fn read_db_should_leave_items_without_due_date() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json");
+            let file_contents = r#"{ "schema_version": 6, "revision": 1, "last_item_id": 2, "epics": { "1": { "name": "Epic 1", "description": "", "status": "Open", "labels": [], "stories": [2] } }, "stories": { "2": { "name": "Story 1", "description": "", "status": "Open", "labels": ["backend"] } }, "trash": { "epics": {}, "stories": {} }, "users": {} }"#;
+            fs::write(&file_path, file_contents).unwrap();
+            let db = JSONFileDatabase {
+                file_path: file_path.to_str().unwrap().to_string(),
+            };
+
+            let db_state = db.read().unwrap();
+            assert_eq!(db_state.epics[&1].due_date, None);
+            assert_eq!(db_state.stories[&2].due_date, None);
+            assert!(dir.path().join("db.json.v6.bak").exists());
+        }
+
+        #[test]
+        fn read_db_should_give_stories_an_empty_thread() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json");
+            let file_contents = r#"{ "schema_version": 7, "revision": 1, "last_item_id": 2, "epics": { "1": { "name": "Epic 1", "description": "", "status": "Open", "stories": [2] } }, "stories": { "2": { "name": "Story 1", "description": "", "status": "Open", "due_date": "2023-05-19" } }, "trash": { "epics": {}, "stories": {} }, "users": {} }"#;
+            fs::write(&file_path, file_contents).unwrap();
+            let db = JSONFileDatabase {
+                file_path: file_path.to_str().unwrap().to_string(),
+            };
+
+            let db_state = db.read().unwrap();
+            assert!(db_state.stories[&2].comments.is_empty());
+            assert!(dir.path().join("db.json.v7.bak").exists());
+        }
+
+        #[test]
+        fn read_db_should_fail_with_newer_schema_version() {
+            let file_contents = r#"{ "schema_version": 999, "revision": 0, "last_item_id": 0, "epics": {}, "stories": {} }"#;
+            let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+            write!(tmpfile, "{}", file_contents).unwrap();
+            let db = JSONFileDatabase {
+                file_path: tmpfile.path().to_str().unwrap().to_string(),
+            };
+
+            assert!(db.read().is_err());
+        }
+
+        #[test]
+        fn write_db_should_not_leave_temp_files() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json");
+            let db = JSONFileDatabase {
+                file_path: file_path.to_str().unwrap().to_string(),
+            };
+            let db_state = DBState::default();
+
+            db.write(&db_state).unwrap();
+            db.write(&db_state).unwrap();
+
+            let file_names = fs::read_dir(dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect::<Vec<_>>();
+            assert_eq!(file_names, vec!["db.json"]);
+            assert_eq!(db.read().unwrap(), db_state);
+        }
+
+        #[test]
+        fn lock_should_fail_while_another_process_holds_it() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json");
+            fs::write(
+                &file_path,
+                r#"{ "schema_version": 8, "revision": 0, "last_item_id": 0, "epics": {}, "stories": {} }"#,
+            )
+            .unwrap();
+            let file_path = file_path.to_str().unwrap().to_string();
+
+            let other_process_db = JSONFileDatabase {
+                file_path: file_path.clone(),
+            };
+            let db = JiraDatabase::new(file_path);
+
+            let lock = other_process_db.lock().unwrap();
+            let result = db.create_epic(Epic::new("".to_owned(), "".to_owned()));
+            assert!(result.unwrap_err().to_string().contains("locked"));
+            assert!(db.read().unwrap().epics.is_empty());
+
+            drop(lock);
+            assert!(db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .is_ok());
+        }
     }
 }
@@ -0,0 +1,835 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    io::{BufRead, BufReader, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::{write_atomically, Database, DatabaseLock};
+use crate::io_utils::current_user;
+use crate::models::{
+    DBState, Epic, FieldChange, Status, Story, Trash, TrashedEpic, TrashedStory, User,
+};
+
+/// A snapshot of the replayed state is stored every this many log entries.
+const SNAPSHOT_INTERVAL: usize = 100;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum Event {
+    EpicCreated {
+        epic_id: u32,
+        epic: Epic,
+    },
+    EpicUpdated {
+        epic_id: u32,
+        epic: Epic,
+    },
+    EpicStatusChanged {
+        epic_id: u32,
+        status: Status,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        change: Option<FieldChange>,
+    },
+    EpicDeleted {
+        epic_id: u32,
+    },
+    StoryCreated {
+        story_id: u32,
+        epic_id: Option<u32>,
+        story: Story,
+    },
+    StoryUpdated {
+        story_id: u32,
+        story: Story,
+    },
+    StoryStatusChanged {
+        story_id: u32,
+        status: Status,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        change: Option<FieldChange>,
+    },
+    StoryDeleted {
+        story_id: u32,
+    },
+    EpicTrashed {
+        epic_id: u32,
+        deleted_at: DateTime<Utc>,
+        deleted_by: String,
+    },
+    StoryTrashed {
+        story_id: u32,
+        epic_id: u32,
+        deleted_at: DateTime<Utc>,
+        deleted_by: String,
+    },
+    EpicRestored {
+        epic_id: u32,
+    },
+    StoryRestored {
+        story_id: u32,
+    },
+    EpicPurged {
+        epic_id: u32,
+    },
+    StoryPurged {
+        story_id: u32,
+    },
+    TrashUpdated {
+        trash: Trash,
+    },
+    UsersUpdated {
+        users: BTreeMap<String, User>,
+    },
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::EpicCreated { epic_id, epic } => {
+                write!(f, "EpicCreated {epic_id} \"{}\"", epic.name)
+            }
+            Event::EpicUpdated { epic_id, epic } => {
+                write!(f, "EpicUpdated {epic_id} \"{}\"", epic.name)
+            }
+            Event::EpicStatusChanged {
+                epic_id, status, ..
+            } => {
+                write!(f, "EpicStatusChanged {epic_id} -> {status}")
+            }
+            Event::EpicDeleted { epic_id } => write!(f, "EpicDeleted {epic_id}"),
+            Event::StoryCreated {
+                story_id, story, ..
+            } => write!(f, "StoryCreated {story_id} \"{}\"", story.name),
+            Event::StoryUpdated { story_id, story } => {
+                write!(f, "StoryUpdated {story_id} \"{}\"", story.name)
+            }
+            Event::StoryStatusChanged {
+                story_id, status, ..
+            } => {
+                write!(f, "StoryStatusChanged {story_id} -> {status}")
+            }
+            Event::StoryDeleted { story_id } => write!(f, "StoryDeleted {story_id}"),
+            Event::EpicTrashed { epic_id, .. } => write!(f, "EpicTrashed {epic_id}"),
+            Event::StoryTrashed { story_id, .. } => write!(f, "StoryTrashed {story_id}"),
+            Event::EpicRestored { epic_id } => write!(f, "EpicRestored {epic_id}"),
+            Event::StoryRestored { story_id } => write!(f, "StoryRestored {story_id}"),
+            Event::EpicPurged { epic_id } => write!(f, "EpicPurged {epic_id}"),
+            Event::StoryPurged { story_id } => write!(f, "StoryPurged {story_id}"),
+            Event::TrashUpdated { .. } => write!(f, "TrashUpdated"),
+            Event::UsersUpdated { users } => {
+                write!(f, "UsersUpdated {}", users.keys().join(", "))
+            }
+        }
+    }
+}
+
+/// One line of the log: everything a single write changed, and who made it when.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct LogEntry {
+    pub at: DateTime<Utc>,
+    pub actor: String,
+    pub revision: u64,
+    pub last_item_id: u32,
+    pub events: Vec<Event>,
+}
+
+/// State replayed up to `offset` bytes (`entries` lines) into the log.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct Replayed {
+    offset: u64,
+    entries: usize,
+    at: Option<DateTime<Utc>>,
+    state: DBState,
+}
+
+/// Event-sourced storage: an append-only log of `LogEntry` lines is the source of truth
+/// and `DBState` is rebuilt by replaying it, starting from the latest snapshot.
+///
+/// Writes are diffed against the last state read, so every `JiraDatabase` mutation ends
+/// up as one entry of fine-grained events.
+pub struct EventLogDatabase {
+    pub file_path: String,
+    replayed: RefCell<Option<Replayed>>,
+    /// The actor of the entries written, `$USER` unless set.
+    author: RefCell<String>,
+}
+
+impl EventLogDatabase {
+    pub fn new(file_path: String) -> Self {
+        Self {
+            file_path,
+            replayed: RefCell::new(None),
+            author: RefCell::new(current_user()),
+        }
+    }
+
+    /// Starts a new log from an existing JSON database file.
+    pub fn import_json(&self, json_file_path: &str) -> Result<DBState> {
+        let _lock = self.lock()?;
+        if Path::new(&self.file_path).exists() {
+            return Err(anyhow!(
+                "Refusing to import into existing log {}",
+                self.file_path
+            ));
+        }
+
+        let json_database = super::JSONFileDatabase {
+            file_path: json_file_path.to_owned(),
+        };
+        let db_state = json_database
+            .read()
+            .with_context(|| format!("Failed to read json database at {json_file_path}"))?;
+
+        *self.replayed.borrow_mut() = Some(Replayed::default());
+        self.write(&db_state)?;
+        Ok(db_state)
+    }
+
+    /// Rebuilds the board as it was at `at`.
+    pub fn read_at(&self, at: DateTime<Utc>) -> Result<DBState> {
+        let snapshot = self
+            .read_snapshot()?
+            .filter(|snapshot| snapshot.at.is_none_or(|snapshot_at| snapshot_at <= at))
+            .unwrap_or_default();
+
+        Ok(self.replay(snapshot, Some(at))?.state)
+    }
+
+    pub fn entries(&self) -> Result<Vec<LogEntry>> {
+        let Some(file) = self.open_log()? else {
+            return Ok(Vec::new());
+        };
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if let Ok(entry) = serde_json::from_str(&line) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    fn snapshot_path(&self) -> String {
+        format!("{}.snapshot", self.file_path)
+    }
+
+    fn read_snapshot(&self) -> Result<Option<Replayed>> {
+        let snapshot_path = self.snapshot_path();
+        if !Path::new(&snapshot_path).exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read(&snapshot_path)
+            .with_context(|| format!("Failed to read snapshot {snapshot_path}"))?;
+        let snapshot = serde_json::from_slice(&contents)
+            .with_context(|| format!("Failed to parse snapshot {snapshot_path}"))?;
+        Ok(Some(snapshot))
+    }
+
+    fn open_log(&self) -> Result<Option<fs::File>> {
+        match fs::File::open(&self.file_path) {
+            Ok(file) => Ok(Some(file)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to open {}", self.file_path)),
+        }
+    }
+
+    /// Applies the entries after `from.offset`, stopping before the first entry newer
+    /// than `until`. A trailing line without a newline is a torn write and is ignored.
+    fn replay(&self, from: Replayed, until: Option<DateTime<Utc>>) -> Result<Replayed> {
+        let Some(mut file) = self.open_log()? else {
+            return Ok(from);
+        };
+        if file.metadata()?.len() < from.offset {
+            return self.replay(Replayed::default(), until);
+        }
+        file.seek(SeekFrom::Start(from.offset))?;
+
+        let mut replayed = from;
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 || !line.ends_with('\n') {
+                break;
+            }
+
+            let entry: LogEntry = serde_json::from_str(&line).with_context(|| {
+                format!(
+                    "Corrupt entry {} in {}",
+                    replayed.entries + 1,
+                    self.file_path
+                )
+            })?;
+            if until.is_some_and(|until| entry.at > until) {
+                break;
+            }
+
+            apply_entry(&mut replayed.state, &entry);
+            replayed.offset += read as u64;
+            replayed.entries += 1;
+            replayed.at = Some(entry.at);
+        }
+
+        Ok(replayed)
+    }
+
+    fn load(&self) -> Result<Replayed> {
+        let cached = self.replayed.borrow_mut().take();
+        let from = match cached {
+            Some(cached) => cached,
+            None => self.read_snapshot()?.unwrap_or_default(),
+        };
+        self.replay(from, None)
+    }
+
+    fn append(&self, from: &Replayed, entry: &LogEntry) -> Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&self.file_path)
+            .with_context(|| format!("Failed to open {}", self.file_path))?;
+
+        // Drop whatever a crashed writer left after the last complete entry.
+        file.set_len(from.offset)?;
+        file.seek(SeekFrom::Start(from.offset))?;
+
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    fn write_snapshot(&self, replayed: &Replayed) -> Result<()> {
+        let contents = serde_json::to_vec(replayed)?;
+        write_atomically(Path::new(&self.snapshot_path()), &contents)
+            .with_context(|| format!("Failed to write snapshot {}", self.snapshot_path()))
+    }
+}
+
+impl Database for EventLogDatabase {
+    fn read(&self) -> Result<DBState> {
+        let replayed = self.load()?;
+        let db_state = replayed.state.clone();
+        *self.replayed.borrow_mut() = Some(replayed);
+        Ok(db_state)
+    }
+
+    fn write(&self, db_state: &DBState) -> Result<()> {
+        let previous = self.load()?;
+
+        let entry = LogEntry {
+            at: Utc::now(),
+            actor: self.author.borrow().clone(),
+            revision: db_state.revision,
+            last_item_id: db_state.last_item_id,
+            events: diff(&previous.state, db_state),
+        };
+        self.append(&previous, &entry)?;
+
+        let mut replayed = previous;
+        apply_entry(&mut replayed.state, &entry);
+        replayed.offset += serde_json::to_vec(&entry)?.len() as u64 + 1;
+        replayed.entries += 1;
+        replayed.at = Some(entry.at);
+
+        if replayed.entries % SNAPSHOT_INTERVAL == 0 {
+            self.write_snapshot(&replayed)?;
+        }
+        *self.replayed.borrow_mut() = Some(replayed);
+        Ok(())
+    }
+
+    fn set_author(&self, author: &str) {
+        *self.author.borrow_mut() = author.to_owned();
+    }
+
+    fn lock(&self) -> Result<DatabaseLock> {
+        DatabaseLock::acquire(&self.file_path)
+    }
+}
+
+fn apply_entry(state: &mut DBState, entry: &LogEntry) {
+    for event in &entry.events {
+        apply(state, event);
+    }
+    state.revision = entry.revision;
+    state.last_item_id = entry.last_item_id;
+}
+
+fn apply(state: &mut DBState, event: &Event) {
+    match event {
+        Event::EpicCreated { epic_id, epic } | Event::EpicUpdated { epic_id, epic } => {
+            state.epics.insert(*epic_id, epic.clone());
+        }
+        Event::EpicStatusChanged {
+            epic_id,
+            status,
+            change,
+        } => {
+            if let Some(epic) = state.epics.get_mut(epic_id) {
+                epic.status = status.clone();
+                if let Some(change) = change {
+                    epic.record_change(change.clone());
+                }
+            }
+        }
+        Event::EpicDeleted { epic_id } => {
+            state.epics.remove(epic_id);
+        }
+        Event::StoryCreated {
+            story_id,
+            epic_id,
+            story,
+        } => {
+            state.stories.insert(*story_id, story.clone());
+            if let Some(epic) = epic_id.and_then(|epic_id| state.epics.get_mut(&epic_id)) {
+                if !epic.stories.contains(story_id) {
+                    epic.stories.push(*story_id);
+                }
+            }
+        }
+        Event::StoryUpdated { story_id, story } => {
+            state.stories.insert(*story_id, story.clone());
+        }
+        Event::StoryStatusChanged {
+            story_id,
+            status,
+            change,
+        } => {
+            if let Some(story) = state.stories.get_mut(story_id) {
+                story.status = status.clone();
+                if let Some(change) = change {
+                    story.record_change(change.clone());
+                }
+            }
+        }
+        Event::StoryDeleted { story_id } => {
+            state.stories.remove(story_id);
+            for epic in state.epics.values_mut() {
+                epic.stories.retain(|id| id != story_id);
+            }
+        }
+        Event::EpicTrashed {
+            epic_id,
+            deleted_at,
+            deleted_by,
+        } => {
+            if let Some(epic) = state.epics.remove(epic_id) {
+                let trashed = TrashedEpic {
+                    epic,
+                    deleted_at: *deleted_at,
+                    deleted_by: deleted_by.clone(),
+                };
+                state.trash.epics.insert(*epic_id, trashed);
+            }
+        }
+        Event::StoryTrashed {
+            story_id,
+            epic_id,
+            deleted_at,
+            deleted_by,
+        } => {
+            if let Some(story) = state.stories.remove(story_id) {
+                if let Some(epic) = state.epics.get_mut(epic_id) {
+                    epic.stories.retain(|id| id != story_id);
+                }
+                let trashed = TrashedStory {
+                    story,
+                    epic_id: *epic_id,
+                    deleted_at: *deleted_at,
+                    deleted_by: deleted_by.clone(),
+                };
+                state.trash.stories.insert(*story_id, trashed);
+            }
+        }
+        Event::EpicRestored { epic_id } => {
+            if let Some(trashed) = state.trash.epics.remove(epic_id) {
+                state.epics.insert(*epic_id, trashed.epic);
+            }
+        }
+        Event::StoryRestored { story_id } => {
+            if let Some(trashed) = state.trash.stories.remove(story_id) {
+                state.stories.insert(*story_id, trashed.story);
+                if let Some(epic) = state.epics.get_mut(&trashed.epic_id) {
+                    if !epic.stories.contains(story_id) {
+                        epic.stories.push(*story_id);
+                    }
+                }
+            }
+        }
+        Event::EpicPurged { epic_id } => {
+            state.trash.epics.remove(epic_id);
+        }
+        Event::StoryPurged { story_id } => {
+            state.trash.stories.remove(story_id);
+        }
+        Event::TrashUpdated { trash } => {
+            state.trash = trash.clone();
+        }
+        Event::UsersUpdated { users } => {
+            state.users = users.clone();
+        }
+    }
+}
+
+/// Describes the change from `previous` to `next` as events. Whatever the specific
+/// events don't capture (renames, reordering, ...) is recorded as a full update of the
+/// epic or story, so replaying the events always yields exactly `next`.
+fn diff(previous: &DBState, next: &DBState) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    // Users go first, so the items assigned to them never refer to unknown users.
+    if previous.users != next.users {
+        events.push(Event::UsersUpdated {
+            users: next.users.clone(),
+        });
+    }
+
+    // Epics go to the trash before their stories, so they keep their story list.
+    for epic_id in previous.epics.keys().sorted() {
+        if next.epics.contains_key(epic_id) {
+            continue;
+        }
+        match next.trash.epics.get(epic_id) {
+            Some(trashed) if !previous.trash.epics.contains_key(epic_id) => {
+                events.push(Event::EpicTrashed {
+                    epic_id: *epic_id,
+                    deleted_at: trashed.deleted_at,
+                    deleted_by: trashed.deleted_by.clone(),
+                })
+            }
+            _ => events.push(Event::EpicDeleted { epic_id: *epic_id }),
+        }
+    }
+    for story_id in previous.stories.keys().sorted() {
+        if next.stories.contains_key(story_id) {
+            continue;
+        }
+        match next.trash.stories.get(story_id) {
+            Some(trashed) if !previous.trash.stories.contains_key(story_id) => {
+                events.push(Event::StoryTrashed {
+                    story_id: *story_id,
+                    epic_id: trashed.epic_id,
+                    deleted_at: trashed.deleted_at,
+                    deleted_by: trashed.deleted_by.clone(),
+                })
+            }
+            _ => events.push(Event::StoryDeleted {
+                story_id: *story_id,
+            }),
+        }
+    }
+
+    for epic_id in previous.trash.epics.keys().sorted() {
+        if !next.trash.epics.contains_key(epic_id) && !next.epics.contains_key(epic_id) {
+            events.push(Event::EpicPurged { epic_id: *epic_id });
+        }
+    }
+    for story_id in previous.trash.stories.keys().sorted() {
+        if !next.trash.stories.contains_key(story_id) && !next.stories.contains_key(story_id) {
+            events.push(Event::StoryPurged {
+                story_id: *story_id,
+            });
+        }
+    }
+
+    for (epic_id, epic) in next.epics.iter().sorted_by_key(|(id, _)| **id) {
+        match previous.epics.get(epic_id) {
+            None if previous.trash.epics.contains_key(epic_id) => {
+                events.push(Event::EpicRestored { epic_id: *epic_id })
+            }
+            None => events.push(Event::EpicCreated {
+                epic_id: *epic_id,
+                epic: epic.clone(),
+            }),
+            Some(previous_epic) if previous_epic.status != epic.status => {
+                events.push(Event::EpicStatusChanged {
+                    epic_id: *epic_id,
+                    status: epic.status.clone(),
+                    change: status_change(&previous_epic.history, &epic.history),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for (story_id, story) in next.stories.iter().sorted_by_key(|(id, _)| **id) {
+        match previous.stories.get(story_id) {
+            None if previous.trash.stories.contains_key(story_id) => {
+                events.push(Event::StoryRestored {
+                    story_id: *story_id,
+                })
+            }
+            None => events.push(Event::StoryCreated {
+                story_id: *story_id,
+                epic_id: next
+                    .epics
+                    .iter()
+                    .find(|(_, epic)| epic.stories.contains(story_id))
+                    .map(|(epic_id, _)| *epic_id),
+                story: story.clone(),
+            }),
+            Some(previous_story) if previous_story.status != story.status => {
+                events.push(Event::StoryStatusChanged {
+                    story_id: *story_id,
+                    status: story.status.clone(),
+                    change: status_change(&previous_story.history, &story.history),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    let mut replayed = previous.clone();
+    for event in &events {
+        apply(&mut replayed, event);
+    }
+    for (epic_id, epic) in next.epics.iter().sorted_by_key(|(id, _)| **id) {
+        if replayed.epics.get(epic_id) != Some(epic) {
+            events.push(Event::EpicUpdated {
+                epic_id: *epic_id,
+                epic: epic.clone(),
+            });
+        }
+    }
+    for (story_id, story) in next.stories.iter().sorted_by_key(|(id, _)| **id) {
+        if replayed.stories.get(story_id) != Some(story) {
+            events.push(Event::StoryUpdated {
+                story_id: *story_id,
+                story: story.clone(),
+            });
+        }
+    }
+    if replayed.trash != next.trash {
+        events.push(Event::TrashUpdated {
+            trash: next.trash.clone(),
+        });
+    }
+
+    events
+}
+
+/// The history entry a status change appended, if that's the only thing added.
+fn status_change(previous: &[FieldChange], next: &[FieldChange]) -> Option<FieldChange> {
+    match next.split_last() {
+        Some((change, rest)) if rest == previous && change.field == "status" => {
+            Some(change.clone())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::JiraDatabase;
+
+    fn build_db(dir: &tempfile::TempDir) -> JiraDatabase {
+        let file_path = dir.path().join("events.log");
+        JiraDatabase::from_database(Box::new(EventLogDatabase::new(
+            file_path.to_str().unwrap().to_string(),
+        )))
+    }
+
+    fn event_log(dir: &tempfile::TempDir) -> EventLogDatabase {
+        EventLogDatabase::new(dir.path().join("events.log").to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn mutations_should_append_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = build_db(&dir);
+
+        let epic_id = db
+            .create_epic(Epic::new("Epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("Story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        db.delete_story(epic_id, story_id).unwrap();
+        db.restore_story(story_id).unwrap();
+        db.delete_epic(epic_id).unwrap();
+        db.purge_epic(epic_id).unwrap();
+
+        let entries = event_log(&dir).entries().unwrap();
+        let events = entries
+            .iter()
+            .map(|entry| entry.events.iter().map(Event::to_string).collect())
+            .collect::<Vec<Vec<_>>>();
+        assert_eq!(
+            events,
+            vec![
+                vec![format!("EpicCreated {epic_id} \"Epic\"")],
+                vec![format!("StoryCreated {story_id} \"Story\"")],
+                vec![format!("StoryStatusChanged {story_id} -> In Progress")],
+                vec![format!("StoryTrashed {story_id}")],
+                vec![format!("StoryRestored {story_id}")],
+                vec![
+                    format!("EpicTrashed {epic_id}"),
+                    format!("StoryTrashed {story_id}")
+                ],
+                vec![
+                    format!("EpicPurged {epic_id}"),
+                    format!("StoryPurged {story_id}")
+                ],
+            ]
+        );
+        let (_, written) = db.take_last_write().unwrap();
+        assert_eq!(event_log(&dir).read().unwrap(), written);
+        let Event::StoryStatusChanged {
+            change: Some(change),
+            ..
+        } = &entries[2].events[0]
+        else {
+            panic!("expected the status change to carry its history entry");
+        };
+        assert_eq!(
+            (change.from.as_str(), change.to.as_str()),
+            ("Open", "In Progress")
+        );
+    }
+
+    #[test]
+    fn writes_should_be_recorded_as_made_by_the_current_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = build_db(&dir);
+        let username = format!("not-{}", current_user());
+        db.set_current_user(username.clone());
+
+        let epic_id = db
+            .create_epic(Epic::new("Epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("Story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        db.delete_story(epic_id, story_id).unwrap();
+
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.epics[&epic_id].created_by, Some(username.clone()));
+        let trashed = &db_state.trash.stories[&story_id];
+        assert_eq!(trashed.deleted_by, username);
+        assert_eq!(trashed.story.created_by, Some(username.clone()));
+        assert_eq!(trashed.story.history[0].author, username);
+        let actors = event_log(&dir)
+            .entries()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.actor)
+            .collect::<Vec<_>>();
+        assert_eq!(actors, vec![username; 4]);
+    }
+
+    #[test]
+    fn read_should_replay_the_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = build_db(&dir);
+
+        let epic_id = db
+            .create_epic(Epic::new("Epic".to_owned(), "".to_owned()))
+            .unwrap();
+        db.create_story(Story::new("Story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_epic_status(epic_id, Status::Closed).unwrap();
+        let expected = db.read().unwrap();
+
+        let replayed = build_db(&dir).read().unwrap();
+        assert_eq!(replayed, expected);
+        assert_eq!(replayed.revision, 3);
+    }
+
+    #[test]
+    fn diff_should_replay_to_the_next_state() {
+        let mut previous = DBState::default();
+        let mut epic = Epic::new("Epic".to_owned(), "".to_owned());
+        epic.stories = vec![2, 3];
+        previous.epics.insert(1, epic);
+        previous
+            .stories
+            .insert(2, Story::new("Two".to_owned(), "".to_owned()));
+        previous
+            .stories
+            .insert(3, Story::new("Three".to_owned(), "".to_owned()));
+
+        let mut next = previous.clone();
+        next.epics.get_mut(&1).unwrap().stories = vec![3, 2];
+        next.stories.get_mut(&2).unwrap().name = "Renamed".to_owned();
+        next.users.insert("alice".to_owned(), User::default());
+        next.stories.get_mut(&3).unwrap().assignee = Some("alice".to_owned());
+
+        let mut replayed = previous.clone();
+        for event in diff(&previous, &next) {
+            apply(&mut replayed, &event);
+        }
+        assert_eq!(replayed, next);
+    }
+
+    #[test]
+    fn read_should_ignore_a_torn_last_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = build_db(&dir);
+        db.create_epic(Epic::new("Epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let log_path = dir.path().join("events.log");
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        write!(file, r#"{{"at": "2023-"#).unwrap();
+
+        let db = build_db(&dir);
+        assert_eq!(db.read().unwrap().epics.len(), 1);
+        db.create_epic(Epic::new("Epic 2".to_owned(), "".to_owned()))
+            .unwrap();
+        assert_eq!(build_db(&dir).read().unwrap().epics.len(), 2);
+    }
+
+    #[test]
+    fn snapshots_should_be_written_periodically() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = build_db(&dir);
+        for _ in 0..SNAPSHOT_INTERVAL {
+            db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+        }
+
+        let log = event_log(&dir);
+        let snapshot = log.read_snapshot().unwrap().unwrap();
+        assert_eq!(snapshot.entries, SNAPSHOT_INTERVAL);
+        assert_eq!(snapshot.state.epics.len(), SNAPSHOT_INTERVAL);
+        assert_eq!(log.read().unwrap(), snapshot.state);
+    }
+
+    #[test]
+    fn read_at_should_rebuild_past_states() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = build_db(&dir);
+        let epic_id = db
+            .create_epic(Epic::new("Epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let log = event_log(&dir);
+        let created_at = log.entries().unwrap()[0].at;
+
+        db.update_epic_status(epic_id, Status::Closed).unwrap();
+
+        let past = log.read_at(created_at).unwrap();
+        assert_eq!(past.epics[&epic_id].status, Status::Open);
+        let before_anything = log
+            .read_at(created_at - chrono::Duration::seconds(1))
+            .unwrap();
+        assert!(before_anything.epics.is_empty());
+    }
+}
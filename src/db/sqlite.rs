@@ -0,0 +1,794 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{
+    params,
+    types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef},
+    Connection, OptionalExtension, ToSql, Transaction,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{Database, DatabaseLock, JSONFileDatabase};
+use crate::models::{DBState, Epic, Priority, Status, Story, Trash, User};
+
+/// `MIGRATIONS[n]` takes the schema from `PRAGMA user_version` n to n + 1.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS epics (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        status TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS stories (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        status TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS epic_stories (
+        epic_id INTEGER NOT NULL REFERENCES epics(id) ON DELETE CASCADE,
+        story_id INTEGER NOT NULL UNIQUE REFERENCES stories(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        PRIMARY KEY (epic_id, story_id)
+    );
+    ",
+    "
+    ALTER TABLE epics ADD COLUMN created_at TEXT;
+    ALTER TABLE epics ADD COLUMN created_by TEXT;
+    ALTER TABLE epics ADD COLUMN updated_at TEXT;
+    ALTER TABLE epics ADD COLUMN history TEXT NOT NULL DEFAULT '[]';
+    ALTER TABLE stories ADD COLUMN created_at TEXT;
+    ALTER TABLE stories ADD COLUMN created_by TEXT;
+    ALTER TABLE stories ADD COLUMN updated_at TEXT;
+    ALTER TABLE stories ADD COLUMN history TEXT NOT NULL DEFAULT '[]';
+    ",
+    "
+    CREATE TABLE trashed_epics (
+        id INTEGER PRIMARY KEY,
+        item TEXT NOT NULL
+    );
+    CREATE TABLE trashed_stories (
+        id INTEGER PRIMARY KEY,
+        item TEXT NOT NULL
+    );
+    ",
+    "
+    ALTER TABLE epics ADD COLUMN priority TEXT NOT NULL DEFAULT 'Medium';
+    ALTER TABLE stories ADD COLUMN priority TEXT NOT NULL DEFAULT 'Medium';
+    ",
+    "
+    CREATE TABLE users (
+        username TEXT PRIMARY KEY,
+        name TEXT NOT NULL
+    );
+    ALTER TABLE epics ADD COLUMN owner TEXT;
+    ALTER TABLE stories ADD COLUMN assignee TEXT;
+    ",
+    "
+    ALTER TABLE epics ADD COLUMN labels TEXT NOT NULL DEFAULT '[]';
+    ALTER TABLE stories ADD COLUMN labels TEXT NOT NULL DEFAULT '[]';
+    ",
+    "
+    ALTER TABLE epics ADD COLUMN due_date TEXT;
+    ALTER TABLE stories ADD COLUMN due_date TEXT;
+    ",
+    "
+    ALTER TABLE stories ADD COLUMN comments TEXT NOT NULL DEFAULT '[]';
+    ",
+];
+
+const EPIC_COLUMNS: &str = "id, name, description, status, created_at, created_by, updated_at, \
+    history, priority, owner, labels, due_date";
+const STORY_COLUMNS: &str = "id, name, description, status, created_at, created_by, updated_at, \
+    history, priority, assignee, labels, due_date, comments";
+
+/// Stores the board in a SQLite file, one row per epic/story plus a relation table
+/// for the epic -> story lists.
+///
+/// `write` only touches the rows that differ from the last state handed out: creating a
+/// story inserts its row and one relation row, updating its status rewrites its row.
+/// `read` checks the single revision row and reuses that state while it's unchanged.
+/// Once another writer moved it, the whole board is loaded again: `JiraDatabase` checks,
+/// changes and undoes whole `DBState`s, so there are no lookups of single rows to make.
+pub struct SqliteDatabase {
+    connection: Connection,
+    file_path: Option<String>,
+    snapshot: RefCell<Option<DBState>>,
+}
+
+impl SqliteDatabase {
+    pub fn open(file_path: &str) -> Result<Self> {
+        let connection = Connection::open(file_path)
+            .with_context(|| format!("Failed to open sqlite database at {file_path}"))?;
+        Self::from_connection(connection, Some(file_path.to_owned()))
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?, None)
+    }
+
+    fn from_connection(connection: Connection, file_path: Option<String>) -> Result<Self> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        let db = Self {
+            connection,
+            file_path,
+            snapshot: RefCell::new(None),
+        };
+
+        {
+            let _lock = db.lock()?;
+            db.migrate().context("Failed to migrate sqlite schema")?;
+        }
+        Ok(db)
+    }
+
+    fn migrate(&self) -> Result<()> {
+        let version: u32 = self
+            .connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let transaction = self.connection.unchecked_transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", index as u32 + 1)?;
+            transaction.commit()?;
+        }
+        Ok(())
+    }
+
+    /// One-shot import of an existing JSON database file. Refuses to run against a
+    /// database that already holds data so it cannot clobber a board by accident.
+    pub fn import_json(&self, json_file_path: &str) -> Result<DBState> {
+        let _lock = self.lock()?;
+        let current = self.load()?;
+        if current.last_item_id != 0
+            || !current.epics.is_empty()
+            || !current.stories.is_empty()
+            || !current.trash.is_empty()
+            || !current.users.is_empty()
+        {
+            return Err(anyhow!(
+                "Refusing to import into a non-empty sqlite database"
+            ));
+        }
+
+        let json_database = JSONFileDatabase {
+            file_path: json_file_path.to_owned(),
+        };
+        let db_state = json_database
+            .read()
+            .with_context(|| format!("Failed to read json database at {json_file_path}"))?;
+
+        *self.snapshot.borrow_mut() = Some(current);
+        self.write(&db_state)?;
+        Ok(db_state)
+    }
+
+    fn load(&self) -> Result<DBState> {
+        let revision = self.read_meta::<i64>("revision")?.unwrap_or(0) as u64;
+        let last_item_id = self.read_meta("last_item_id")?.unwrap_or(0);
+
+        let mut statement = self
+            .connection
+            .prepare(&format!("SELECT {EPIC_COLUMNS} FROM epics"))?;
+        let mut epics = statement
+            .query_map([], |row| {
+                let epic = Epic {
+                    name: row.get("name")?,
+                    description: row.get("description")?,
+                    status: row.get("status")?,
+                    priority: row.get("priority")?,
+                    owner: row.get("owner")?,
+                    labels: row.get::<_, Json<_>>("labels")?.0,
+                    due_date: row.get("due_date")?,
+                    stories: Vec::new(),
+                    created_at: row.get("created_at")?,
+                    created_by: row.get("created_by")?,
+                    updated_at: row.get("updated_at")?,
+                    history: row.get::<_, Json<_>>("history")?.0,
+                };
+                Ok((row.get("id")?, epic))
+            })?
+            .collect::<rusqlite::Result<HashMap<u32, Epic>>>()?;
+
+        let mut statement = self
+            .connection
+            .prepare(&format!("SELECT {STORY_COLUMNS} FROM stories"))?;
+        let stories = statement
+            .query_map([], |row| {
+                let story = Story {
+                    name: row.get("name")?,
+                    description: row.get("description")?,
+                    status: row.get("status")?,
+                    priority: row.get("priority")?,
+                    assignee: row.get("assignee")?,
+                    labels: row.get::<_, Json<_>>("labels")?.0,
+                    due_date: row.get("due_date")?,
+                    comments: row.get::<_, Json<_>>("comments")?.0,
+                    created_at: row.get("created_at")?,
+                    created_by: row.get("created_by")?,
+                    updated_at: row.get("updated_at")?,
+                    history: row.get::<_, Json<_>>("history")?.0,
+                };
+                Ok((row.get("id")?, story))
+            })?
+            .collect::<rusqlite::Result<HashMap<u32, Story>>>()?;
+
+        let mut statement = self
+            .connection
+            .prepare("SELECT epic_id, story_id FROM epic_stories ORDER BY epic_id, position")?;
+        let relations = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        for relation in relations {
+            let (epic_id, story_id): (u32, u32) = relation?;
+            if let Some(epic) = epics.get_mut(&epic_id) {
+                epic.stories.push(story_id);
+            }
+        }
+
+        let trash = Trash {
+            epics: self.load_json_rows("trashed_epics")?,
+            stories: self.load_json_rows("trashed_stories")?,
+        };
+
+        let mut statement = self
+            .connection
+            .prepare("SELECT username, name FROM users")?;
+        let users = statement
+            .query_map([], |row| Ok((row.get(0)?, User { name: row.get(1)? })))?
+            .collect::<rusqlite::Result<BTreeMap<String, User>>>()?;
+
+        Ok(DBState {
+            revision,
+            last_item_id,
+            epics,
+            stories,
+            trash,
+            users,
+        })
+    }
+
+    fn load_json_rows<T: DeserializeOwned>(&self, table: &str) -> Result<HashMap<u32, T>> {
+        let mut statement = self
+            .connection
+            .prepare(&format!("SELECT id, item FROM {table}"))?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, Json<T>>(1)?.0)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(rows)
+    }
+
+    fn read_meta<T: FromSql>(&self, key: &str) -> Result<Option<T>> {
+        let value = self
+            .connection
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        Ok(value)
+    }
+}
+
+impl Database for SqliteDatabase {
+    fn read(&self) -> Result<DBState> {
+        // Every write moves the revision, so an unchanged one means unchanged rows.
+        let revision = self.read_meta::<i64>("revision")?.unwrap_or(0) as u64;
+        let cached = self
+            .snapshot
+            .borrow()
+            .as_ref()
+            .filter(|snapshot| snapshot.revision == revision)
+            .cloned();
+        let db_state = match cached {
+            Some(db_state) => db_state,
+            None => self.load()?,
+        };
+        *self.snapshot.borrow_mut() = Some(db_state.clone());
+        Ok(db_state)
+    }
+
+    fn write(&self, db_state: &DBState) -> Result<()> {
+        let previous = match self.snapshot.borrow_mut().take() {
+            Some(previous) => previous,
+            None => self.load()?,
+        };
+
+        let transaction = self.connection.unchecked_transaction()?;
+        write_changes(&transaction, &previous, db_state)?;
+        transaction
+            .commit()
+            .context("Failed to commit sqlite transaction")?;
+
+        *self.snapshot.borrow_mut() = Some(db_state.clone());
+        Ok(())
+    }
+
+    /// SQLite serializes the writes themselves, but id allocation happens between our
+    /// read and write, so the whole cycle still needs the lock.
+    fn lock(&self) -> Result<DatabaseLock> {
+        match &self.file_path {
+            Some(file_path) => DatabaseLock::acquire(file_path),
+            None => Ok(DatabaseLock { file: None }),
+        }
+    }
+}
+
+fn write_changes(transaction: &Transaction, previous: &DBState, next: &DBState) -> Result<()> {
+    if previous.revision != next.revision {
+        write_meta(transaction, "revision", next.revision as i64)?;
+    }
+    if previous.last_item_id != next.last_item_id {
+        write_meta(transaction, "last_item_id", next.last_item_id)?;
+    }
+
+    for story_id in previous.stories.keys() {
+        if !next.stories.contains_key(story_id) {
+            transaction.execute("DELETE FROM stories WHERE id = ?1", params![story_id])?;
+        }
+    }
+    for epic_id in previous.epics.keys() {
+        if !next.epics.contains_key(epic_id) {
+            transaction.execute("DELETE FROM epics WHERE id = ?1", params![epic_id])?;
+        }
+    }
+
+    for (epic_id, epic) in &next.epics {
+        if previous.epics.get(epic_id) == Some(epic) {
+            continue;
+        }
+        transaction.execute(
+            &upsert_statement("epics", EPIC_COLUMNS),
+            params![
+                epic_id,
+                epic.name,
+                epic.description,
+                epic.status,
+                epic.created_at,
+                epic.created_by,
+                epic.updated_at,
+                Json(&epic.history),
+                epic.priority,
+                epic.owner,
+                Json(&epic.labels),
+                epic.due_date,
+            ],
+        )?;
+    }
+
+    for (story_id, story) in &next.stories {
+        if previous.stories.get(story_id) == Some(story) {
+            continue;
+        }
+        transaction.execute(
+            &upsert_statement("stories", STORY_COLUMNS),
+            params![
+                story_id,
+                story.name,
+                story.description,
+                story.status,
+                story.created_at,
+                story.created_by,
+                story.updated_at,
+                Json(&story.history),
+                story.priority,
+                story.assignee,
+                Json(&story.labels),
+                story.due_date,
+                Json(&story.comments),
+            ],
+        )?;
+    }
+
+    write_json_rows(
+        transaction,
+        "trashed_epics",
+        &previous.trash.epics,
+        &next.trash.epics,
+    )?;
+    write_json_rows(
+        transaction,
+        "trashed_stories",
+        &previous.trash.stories,
+        &next.trash.stories,
+    )?;
+
+    for username in previous.users.keys() {
+        if !next.users.contains_key(username) {
+            transaction.execute("DELETE FROM users WHERE username = ?1", params![username])?;
+        }
+    }
+    for (username, user) in &next.users {
+        if previous.users.get(username) != Some(user) {
+            transaction.execute(
+                "INSERT INTO users (username, name) VALUES (?1, ?2)
+                 ON CONFLICT(username) DO UPDATE SET name = excluded.name",
+                params![username, user.name],
+            )?;
+        }
+    }
+
+    // Stories added to the end of an epic or taken out of it change one relation row
+    // each, an epic whose stories were reordered gets its rows rewritten. All stale rows
+    // go first so a story moving between epics never trips the UNIQUE constraint.
+    let mut appended = Vec::new();
+    for (epic_id, epic) in &next.epics {
+        let previous_stories = previous
+            .epics
+            .get(epic_id)
+            .map_or(&[][..], |previous| &previous.stories);
+        if previous_stories == epic.stories {
+            continue;
+        }
+
+        let kept = previous_stories
+            .iter()
+            .filter(|story_id| epic.stories.contains(story_id))
+            .copied()
+            .collect::<Vec<_>>();
+        if epic.stories.starts_with(&kept) {
+            for story_id in previous_stories.iter().filter(|id| !kept.contains(id)) {
+                transaction.execute(
+                    "DELETE FROM epic_stories WHERE epic_id = ?1 AND story_id = ?2",
+                    params![epic_id, story_id],
+                )?;
+            }
+            appended.extend(epic.stories[kept.len()..].iter().map(|id| (*epic_id, *id)));
+        } else {
+            transaction.execute(
+                "DELETE FROM epic_stories WHERE epic_id = ?1",
+                params![epic_id],
+            )?;
+            appended.extend(epic.stories.iter().map(|id| (*epic_id, *id)));
+        }
+    }
+    for (epic_id, story_id) in appended {
+        transaction.execute(
+            "INSERT INTO epic_stories (epic_id, story_id, position)
+             SELECT ?1, ?2, COALESCE(MAX(position) + 1, 0) FROM epic_stories WHERE epic_id = ?1",
+            params![epic_id, story_id],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Syncs a table of `(id, item)` rows holding json encoded values.
+fn write_json_rows<T: Serialize + PartialEq>(
+    transaction: &Transaction,
+    table: &str,
+    previous: &HashMap<u32, T>,
+    next: &HashMap<u32, T>,
+) -> Result<()> {
+    for id in previous.keys() {
+        if !next.contains_key(id) {
+            transaction.execute(&format!("DELETE FROM {table} WHERE id = ?1"), params![id])?;
+        }
+    }
+    for (id, item) in next {
+        if previous.get(id) != Some(item) {
+            transaction.execute(
+                &upsert_statement(table, "id, item"),
+                params![id, Json(item)],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// `INSERT ... ON CONFLICT(id) DO UPDATE` over `columns`, the first of which is `id`.
+/// Unlike `INSERT OR REPLACE` it keeps the row, so the relation rows don't cascade away.
+fn upsert_statement(table: &str, columns: &str) -> String {
+    let columns = columns.split(", ").collect::<Vec<_>>();
+    let placeholders = (1..=columns.len()).map(|index| format!("?{index}"));
+    let updates = columns[1..]
+        .iter()
+        .map(|column| format!("{column} = excluded.{column}"));
+
+    format!(
+        "INSERT INTO {table} ({}) VALUES ({}) ON CONFLICT(id) DO UPDATE SET {}",
+        columns.join(", "),
+        placeholders.collect::<Vec<_>>().join(", "),
+        updates.collect::<Vec<_>>().join(", ")
+    )
+}
+
+fn write_meta(transaction: &Transaction, key: &str, value: impl ToSql) -> Result<()> {
+    transaction.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Column holding a json encoded value, for nested data like an item's history.
+struct Json<T>(T);
+
+impl<T: Serialize> ToSql for Json<T> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        let json = serde_json::to_string(&self.0)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+        Ok(ToSqlOutput::from(json))
+    }
+}
+
+impl<T: DeserializeOwned> FromSql for Json<T> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        serde_json::from_str(value.as_str()?)
+            .map(Json)
+            .map_err(|e| FromSqlError::Other(e.into()))
+    }
+}
+
+impl ToSql for Status {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        let value = match self {
+            Status::Open => "Open",
+            Status::InProgress => "InProgress",
+            Status::Resolved => "Resolved",
+            Status::Closed => "Closed",
+        };
+        Ok(ToSqlOutput::from(value))
+    }
+}
+
+impl FromSql for Status {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "Open" => Ok(Status::Open),
+            "InProgress" => Ok(Status::InProgress),
+            "Resolved" => Ok(Status::Resolved),
+            "Closed" => Ok(Status::Closed),
+            other => Err(FromSqlError::Other(
+                anyhow!("Unknown status {other}").into(),
+            )),
+        }
+    }
+}
+
+impl ToSql for Priority {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for Priority {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: anyhow::Error| FromSqlError::Other(e.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, io::Write};
+
+    use chrono::{NaiveDate, Utc};
+
+    use super::*;
+    use crate::models::{Comment, TrashedStory};
+
+    fn build_state() -> DBState {
+        let mut epic = Epic::new("Epic 1".to_owned(), "Description 1".to_owned());
+        epic.stories = vec![3, 2];
+        epic.owner = Some("alice".to_owned());
+        epic.due_date = NaiveDate::from_ymd_opt(2023, 6, 1);
+        let mut story = Story::new("Story 3".to_owned(), "".to_owned());
+        story.priority = Priority::High;
+        story.assignee = Some("alice".to_owned());
+        story.labels = BTreeSet::from_iter(["backend".to_owned(), "tech-debt".to_owned()]);
+        story.comments.push(Comment {
+            id: 1,
+            author: "alice".to_owned(),
+            created_at: Utc::now(),
+            edited_at: None,
+            body: "Needs a migration".to_owned(),
+        });
+        let alice = User {
+            name: "Alice".to_owned(),
+        };
+
+        DBState {
+            revision: 1,
+            last_item_id: 3,
+            epics: HashMap::from_iter([(1, epic)]),
+            stories: HashMap::from_iter([
+                (2, Story::new("Story 2".to_owned(), "".to_owned())),
+                (3, story),
+            ]),
+            trash: Trash::default(),
+            users: BTreeMap::from_iter([("alice".to_owned(), alice)]),
+        }
+    }
+
+    #[test]
+    fn read_empty_database_should_work() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        let db_state = db.read().unwrap();
+
+        assert_eq!(db_state.last_item_id, 0);
+        assert!(db_state.epics.is_empty());
+        assert!(db_state.stories.is_empty());
+    }
+
+    #[test]
+    fn write_should_round_trip_and_keep_story_order() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        let db_state = build_state();
+
+        db.write(&db_state).unwrap();
+
+        assert_eq!(db.load().unwrap(), db_state);
+        assert_eq!(db.load().unwrap().epics[&1].stories, vec![3, 2]);
+    }
+
+    #[test]
+    fn write_should_apply_updates_and_deletions() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        db.write(&build_state()).unwrap();
+
+        let mut db_state = db.read().unwrap();
+        db_state.stories.get_mut(&2).unwrap().status = Status::Closed;
+        db_state.stories.remove(&3);
+        db_state.epics.get_mut(&1).unwrap().stories = vec![2];
+        db.write(&db_state).unwrap();
+
+        assert_eq!(db.load().unwrap(), db_state);
+
+        db_state.epics.remove(&1);
+        db_state.stories.clear();
+        db.write(&db_state).unwrap();
+
+        let relation_count: u32 = db
+            .connection
+            .query_row("SELECT COUNT(*) FROM epic_stories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(relation_count, 0);
+        assert_eq!(db.load().unwrap(), db_state);
+    }
+
+    #[test]
+    fn write_should_move_story_between_epics() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        let mut db_state = build_state();
+        db_state
+            .epics
+            .insert(4, Epic::new("Epic 4".to_owned(), "".to_owned()));
+        db.write(&db_state).unwrap();
+
+        let mut db_state = db.read().unwrap();
+        db_state.epics.get_mut(&1).unwrap().stories = vec![2];
+        db_state.epics.get_mut(&4).unwrap().stories = vec![3];
+        db.write(&db_state).unwrap();
+
+        assert_eq!(db.load().unwrap(), db_state);
+    }
+
+    #[test]
+    fn write_should_only_touch_the_changed_relations() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        db.write(&build_state()).unwrap();
+        let positions = |db: &SqliteDatabase| {
+            let mut statement = db
+                .connection
+                .prepare("SELECT story_id, position FROM epic_stories ORDER BY position")
+                .unwrap();
+            statement
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .collect::<rusqlite::Result<Vec<(u32, u32)>>>()
+                .unwrap()
+        };
+        assert_eq!(positions(&db), vec![(3, 0), (2, 1)]);
+
+        let mut db_state = db.read().unwrap();
+        db_state.stories.remove(&3);
+        db_state
+            .stories
+            .insert(4, Story::new("Story 4".to_owned(), "".to_owned()));
+        db_state.epics.get_mut(&1).unwrap().stories = vec![2, 4];
+        db.write(&db_state).unwrap();
+        // Story 2 keeps the row it had, story 4 goes after it.
+        assert_eq!(positions(&db), vec![(2, 1), (4, 2)]);
+        assert_eq!(db.load().unwrap(), db_state);
+
+        db_state.epics.get_mut(&1).unwrap().stories = vec![4, 2];
+        db.write(&db_state).unwrap();
+        assert_eq!(positions(&db), vec![(4, 0), (2, 1)]);
+        assert_eq!(db.load().unwrap(), db_state);
+    }
+
+    #[test]
+    fn read_should_only_reload_when_the_revision_moved() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        let db_state = build_state();
+        db.write(&db_state).unwrap();
+
+        db.connection
+            .execute("UPDATE stories SET name = 'Renamed' WHERE id = 2", [])
+            .unwrap();
+        assert_eq!(db.read().unwrap(), db_state);
+
+        db.connection
+            .execute(
+                "UPDATE meta SET value = value + 1 WHERE key = 'revision'",
+                [],
+            )
+            .unwrap();
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.revision, 2);
+        assert_eq!(db_state.stories[&2].name, "Renamed");
+    }
+
+    #[test]
+    fn write_should_round_trip_the_trash() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        let mut db_state = build_state();
+        let story = db_state.stories.remove(&3).unwrap();
+        db_state.epics.get_mut(&1).unwrap().stories = vec![2];
+        db_state.trash.stories.insert(
+            3,
+            TrashedStory {
+                story,
+                epic_id: 1,
+                deleted_at: chrono::Utc::now(),
+                deleted_by: "alice".to_owned(),
+            },
+        );
+        db.write(&db_state).unwrap();
+        assert_eq!(db.load().unwrap(), db_state);
+
+        db_state.trash.stories.clear();
+        db.write(&db_state).unwrap();
+        assert_eq!(db.load().unwrap(), db_state);
+    }
+
+    #[test]
+    fn open_should_migrate_an_older_schema() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(MIGRATIONS[0]).unwrap();
+        connection
+            .execute(
+                "INSERT INTO stories (id, name, description, status) VALUES (2, 'Story 2', '', 'Open')",
+                [],
+            )
+            .unwrap();
+
+        let db = SqliteDatabase::from_connection(connection, None).unwrap();
+        let story = &db.read().unwrap().stories[&2];
+        assert_eq!(story.name, "Story 2");
+        assert_eq!(story.created_at, None);
+        assert!(story.history.is_empty());
+
+        let version: u32 = db
+            .connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version as usize, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn import_json_should_copy_the_json_database() {
+        let db_state = build_state();
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        write!(tmpfile, "{}", serde_json::to_string(&db_state).unwrap()).unwrap();
+        let json_path = tmpfile.path().to_str().unwrap();
+
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        db.import_json(json_path).unwrap();
+        assert_eq!(db.read().unwrap(), db_state);
+
+        assert!(db.import_json(json_path).is_err());
+    }
+}
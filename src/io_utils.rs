@@ -1,4 +1,17 @@
-use std::io;
+use std::{
+    env, fs,
+    io::{self, Write},
+    process::Command,
+};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Local, NaiveDate};
+
+/// Explains the header of a description opened in the editor.
+pub const DESCRIPTION_HEADER: &str =
+    "Everything below the line is the description, this header is removed.";
+/// Separates the header of a file opened in the editor from the text.
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
 
 pub fn get_user_input() -> String {
     let mut user_input = String::new();
@@ -11,3 +24,84 @@ pub fn get_user_input() -> String {
 pub fn wait_for_key_press() {
     io::stdin().read_line(&mut String::new()).unwrap();
 }
+
+/// Columns of the terminal, 80 when it can't be told, for instance when piped.
+pub fn terminal_width() -> usize {
+    crossterm::terminal::size()
+        .map(|(columns, _)| usize::from(columns))
+        .unwrap_or(80)
+}
+
+/// Username of whoever runs the program, unless the config file names another one.
+pub fn current_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_owned())
+}
+
+/// The local date, which due dates are compared to.
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Lets the user edit `text` in `$VISUAL` or `$EDITOR` (vi without either), below a
+/// commented out `header`. Returns the saved text without the header.
+pub fn edit_in_editor(header: &str, text: &str) -> Result<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_owned());
+    edit_with(&editor, header, text)
+}
+
+fn edit_with(editor: &str, header: &str, text: &str) -> Result<String> {
+    let mut file = tempfile::Builder::new().suffix(".md").tempfile()?;
+    for line in header.lines() {
+        writeln!(file, "# {line}")?;
+    }
+    writeln!(file, "{SCISSORS}")?;
+    write!(file, "{text}")?;
+    file.flush()?;
+
+    // Editors are often configured with arguments, like `code --wait`.
+    let mut words = editor.split_whitespace();
+    let program = words.next().ok_or_else(|| anyhow!("$EDITOR is empty"))?;
+    let status = Command::new(program)
+        .args(words)
+        .arg(file.path())
+        .status()
+        .with_context(|| anyhow!("Failed to start {editor}"))?;
+    if !status.success() {
+        return Err(anyhow!("{editor} exited with {status}"));
+    }
+
+    Ok(strip_header(&fs::read_to_string(file.path())?))
+}
+
+/// Everything below the scissors line, all of `contents` if it was removed.
+fn strip_header(contents: &str) -> String {
+    let text = match contents.split_once(SCISSORS) {
+        Some((_, text)) => text,
+        None => contents,
+    };
+    text.trim().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_header_should_keep_the_text_below_the_scissors() {
+        let contents = format!("# Describe the story\n{SCISSORS}\n# Heading\n\n- one\n- two\n");
+        assert_eq!(strip_header(&contents), "# Heading\n\n- one\n- two");
+        assert_eq!(strip_header("  no header\n"), "no header");
+    }
+
+    #[test]
+    fn edit_with_should_return_the_saved_text() {
+        let text = edit_with("true", "Header", "First line\nSecond line").unwrap();
+        assert_eq!(text, "First line\nSecond line");
+        assert!(edit_with("false", "Header", "").is_err());
+        assert!(edit_with("", "Header", "").is_err());
+    }
+}
@@ -1,14 +1,69 @@
-use std::rc::Rc;
+use std::{
+    io::{self, IsTerminal},
+    process,
+    rc::Rc,
+};
 
+use clap::Parser;
+
+mod cli;
+mod config;
 mod db;
 mod io_utils;
 mod models;
 mod navigator;
+mod tui;
 mod ui;
+mod undo;
 
 fn main() {
-    let db = Rc::new(db::JiraDatabase::new("data/db.json".to_owned()));
-    let mut navigator = navigator::Navigator::new(db);
+    let cli = cli::Cli::parse();
+    let selected = config::Config::load().and_then(|config| {
+        let selection = config.select(cli.db, cli.project.as_deref())?;
+        Ok((config, selection))
+    });
+    let (config, selection) = match selected {
+        Ok(selected) => selected,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            process::exit(1);
+        }
+    };
+
+    match cli.command {
+        Some(command) => {
+            if let Err(e) = cli::run(command, &config, selection) {
+                eprintln!("Error: {:#}", e);
+                process::exit(1);
+            }
+        }
+        None => {
+            let full_screen = !cli.plain && io::stdin().is_terminal() && io::stdout().is_terminal();
+            run_interactive(config, selection, full_screen)
+        }
+    }
+}
+
+fn run_interactive(config: config::Config, selection: config::Selection, full_screen: bool) {
+    let mut navigator = match selection {
+        config::Selection::Database(project) => match open_board(&config, &project) {
+            Ok(navigator) => navigator,
+            Err(e) => {
+                println!("{:#}", e);
+                return;
+            }
+        },
+        config::Selection::Projects(projects) => {
+            navigator::Navigator::with_project_picker(config, projects)
+        }
+    };
+
+    if full_screen {
+        if let Err(e) = tui::run(&mut navigator) {
+            println!("{:#}", e);
+        }
+        return;
+    }
 
     loop {
         clearscreen::clear().unwrap();
@@ -24,10 +79,13 @@ fn main() {
         }
 
         let input = io_utils::get_user_input();
-        match page.handle_input(&input.trim()) {
+        match page.handle_input(input.trim()) {
             Ok(result) => {
                 if let Some(action) = result {
-                    navigator.handle_action(action).ok();
+                    if let Err(err) = navigator.handle_action(action) {
+                        println!("{:#}\nPress any key to continue...", err);
+                        io_utils::wait_for_key_press();
+                    }
                 }
             }
             Err(err) => {
@@ -41,3 +99,20 @@ fn main() {
         }
     }
 }
+
+fn open_board(
+    config: &config::Config,
+    project: &config::ProjectConfig,
+) -> anyhow::Result<navigator::Navigator> {
+    use anyhow::Context;
+
+    let db = config
+        .open_database(project)
+        .context("Error opening database")?;
+    let undo_history = undo::UndoHistory::load(config::undo_history_path(&project.db))
+        .context("Error loading undo history")?;
+
+    let mut navigator = navigator::Navigator::new(Rc::new(db));
+    navigator.set_undo_history(undo_history);
+    Ok(navigator)
+}
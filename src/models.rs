@@ -1,18 +1,63 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Display,
+    str::FromStr,
+};
 
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Action {
     NavigateToEpicDetail { epic_id: u32 },
     NavigateToStoryDetail { epic_id: u32, story_id: u32 },
+    NavigateToEpicHistory { epic_id: u32 },
+    NavigateToStoryHistory { story_id: u32 },
+    NavigateToTrash,
+    NavigateToMyWork,
+    NavigateToDeadlines,
+    NavigateToBoard { epic_id: Option<u32> },
     NavigateToPreviousPage,
+    OpenProject { name: String },
     CreateEpic,
+    UpdateEpicName { epic_id: u32 },
+    UpdateEpicDescription { epic_id: u32 },
     UpdateEpicStatus { epic_id: u32 },
+    UpdateEpicPriority { epic_id: u32 },
+    UpdateEpicOwner { epic_id: u32 },
+    RemoveEpicOwner { epic_id: u32 },
+    AddEpicLabel { epic_id: u32 },
+    RemoveEpicLabel { epic_id: u32 },
+    UpdateEpicDueDate { epic_id: u32 },
+    RemoveEpicDueDate { epic_id: u32 },
     DeleteEpic { epic_id: u32 },
     CreateStory { epic_id: u32 },
-    UpdateStoryStatus { story_id: u32 },
+    UpdateStoryName { story_id: u32 },
+    UpdateStoryDescription { story_id: u32 },
+    UpdateStoryStatus { story_id: u32, to: Option<Status> },
+    UpdateStoryPriority { story_id: u32 },
+    AssignStory { story_id: u32 },
+    UnassignStory { story_id: u32 },
+    AddStoryLabel { story_id: u32 },
+    RemoveStoryLabel { story_id: u32 },
+    UpdateStoryDueDate { story_id: u32 },
+    RemoveStoryDueDate { story_id: u32 },
+    CommentOnStory { story_id: u32 },
+    EditComment { story_id: u32 },
+    DeleteComment { story_id: u32 },
+    MoveStory { story_id: u32 },
+    MoveStoryUp { story_id: u32 },
+    MoveStoryDown { story_id: u32 },
+    MoveStoryToTop { story_id: u32 },
+    RankStoryBefore { story_id: u32, before_id: u32 },
     DeleteStory { epic_id: u32, story_id: u32 },
+    RestoreEpic { epic_id: u32 },
+    RestoreStory { story_id: u32 },
+    PurgeEpic { epic_id: u32 },
+    PurgeStory { story_id: u32 },
+    FilterByLabel,
+    Undo,
+    Redo,
     Exit,
 }
 
@@ -24,23 +69,77 @@ impl Display for Action {
                 epic_id: _,
                 story_id: _,
             } => "NavigateToStoryDetail",
+            Action::NavigateToEpicHistory { epic_id: _ } => "NavigateToEpicHistory",
+            Action::NavigateToStoryHistory { story_id: _ } => "NavigateToStoryHistory",
+            Action::NavigateToTrash => "NavigateToTrash",
+            Action::NavigateToMyWork => "NavigateToMyWork",
+            Action::NavigateToDeadlines => "NavigateToDeadlines",
+            Action::NavigateToBoard { epic_id: _ } => "NavigateToBoard",
             Action::NavigateToPreviousPage => "NavigateToPreviousPage",
+            Action::OpenProject { name: _ } => "OpenProject",
             Action::CreateEpic => "CreateEpic",
+            Action::UpdateEpicName { epic_id: _ } => "UpdateEpicName",
+            Action::UpdateEpicDescription { epic_id: _ } => "UpdateEpicDescription",
             Action::UpdateEpicStatus { epic_id: _ } => "UpdateEpicStatus",
+            Action::UpdateEpicPriority { epic_id: _ } => "UpdateEpicPriority",
+            Action::UpdateEpicOwner { epic_id: _ } => "UpdateEpicOwner",
+            Action::RemoveEpicOwner { epic_id: _ } => "RemoveEpicOwner",
+            Action::AddEpicLabel { epic_id: _ } => "AddEpicLabel",
+            Action::RemoveEpicLabel { epic_id: _ } => "RemoveEpicLabel",
+            Action::UpdateEpicDueDate { epic_id: _ } => "UpdateEpicDueDate",
+            Action::RemoveEpicDueDate { epic_id: _ } => "RemoveEpicDueDate",
             Action::DeleteEpic { epic_id: _ } => "DeleteEpic",
             Action::CreateStory { epic_id: _ } => "CreateStory",
-            Action::UpdateStoryStatus { story_id: _ } => "UpdateStoryStatus",
+            Action::UpdateStoryName { story_id: _ } => "UpdateStoryName",
+            Action::UpdateStoryDescription { story_id: _ } => "UpdateStoryDescription",
+            Action::UpdateStoryStatus { story_id: _, to: _ } => "UpdateStoryStatus",
+            Action::UpdateStoryPriority { story_id: _ } => "UpdateStoryPriority",
+            Action::AssignStory { story_id: _ } => "AssignStory",
+            Action::UnassignStory { story_id: _ } => "UnassignStory",
+            Action::AddStoryLabel { story_id: _ } => "AddStoryLabel",
+            Action::RemoveStoryLabel { story_id: _ } => "RemoveStoryLabel",
+            Action::UpdateStoryDueDate { story_id: _ } => "UpdateStoryDueDate",
+            Action::RemoveStoryDueDate { story_id: _ } => "RemoveStoryDueDate",
+            Action::CommentOnStory { story_id: _ } => "CommentOnStory",
+            Action::EditComment { story_id: _ } => "EditComment",
+            Action::DeleteComment { story_id: _ } => "DeleteComment",
+            Action::MoveStory { story_id: _ } => "MoveStory",
+            Action::MoveStoryUp { story_id: _ } => "MoveStoryUp",
+            Action::MoveStoryDown { story_id: _ } => "MoveStoryDown",
+            Action::MoveStoryToTop { story_id: _ } => "MoveStoryToTop",
+            Action::RankStoryBefore {
+                story_id: _,
+                before_id: _,
+            } => "RankStoryBefore",
             Action::DeleteStory {
                 epic_id: _,
                 story_id: _,
             } => "DeleteStory",
+            Action::RestoreEpic { epic_id: _ } => "RestoreEpic",
+            Action::RestoreStory { story_id: _ } => "RestoreStory",
+            Action::PurgeEpic { epic_id: _ } => "PurgeEpic",
+            Action::PurgeStory { story_id: _ } => "PurgeStory",
+            Action::FilterByLabel => "FilterByLabel",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
             Action::Exit => "Exit",
         };
         write!(f, "{result}")
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+/// Where to move a story among the stories of its epic, which are ranked first to last.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StoryRank {
+    Up,
+    Down,
+    Top,
+    /// Right before another story of the same epic.
+    Before(u32),
+}
+
+/// Ordered by progress, open first.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
 pub enum Status {
     Open,
     InProgress,
@@ -48,6 +147,20 @@ pub enum Status {
     Closed,
 }
 
+impl Status {
+    pub const ALL: [Status; 4] = [
+        Status::Open,
+        Status::InProgress,
+        Status::Resolved,
+        Status::Closed,
+    ];
+
+    /// Resolved or closed.
+    pub fn is_done(&self) -> bool {
+        matches!(self, Status::Resolved | Status::Closed)
+    }
+}
+
 impl std::fmt::Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let result = match self {
@@ -61,23 +174,225 @@ impl std::fmt::Display for Status {
     }
 }
 
+impl FromStr for Status {
+    type Err = anyhow::Error;
+
+    /// Accepts `open`, `in-progress`, `resolved` and `closed`, ignoring case and
+    /// separators.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        match normalized.as_str() {
+            "open" => Ok(Status::Open),
+            "inprogress" => Ok(Status::InProgress),
+            "resolved" => Ok(Status::Resolved),
+            "closed" => Ok(Status::Closed),
+            _ => Err(anyhow::anyhow!(
+                "Unknown status \"{s}\", expected open, in-progress, resolved or closed"
+            )),
+        }
+    }
+}
+
+/// Declared from most to least urgent, so sorting by priority puts `Highest` first.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Default)]
+pub enum Priority {
+    Highest,
+    High,
+    #[default]
+    Medium,
+    Low,
+    Lowest,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 5] = [
+        Priority::Highest,
+        Priority::High,
+        Priority::Medium,
+        Priority::Low,
+        Priority::Lowest,
+    ];
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let result = match self {
+            Priority::Highest => "Highest",
+            Priority::High => "High",
+            Priority::Medium => "Medium",
+            Priority::Low => "Low",
+            Priority::Lowest => "Lowest",
+        };
+
+        write!(f, "{result}")
+    }
+}
+
+impl FromStr for Priority {
+    type Err = anyhow::Error;
+
+    /// Accepts the names ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Priority::ALL
+            .into_iter()
+            .find(|priority| priority.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown priority \"{s}\", expected highest, high, medium, low or lowest"
+                )
+            })
+    }
+}
+
+/// An item as users refer to it: `PAY-17`, or just `17`. The prefix is matched
+/// ignoring case.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ItemKey {
+    pub prefix: Option<String>,
+    pub id: u32,
+}
+
+impl FromStr for ItemKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow::anyhow!("Expected an item key like PAY-17 or an id, got \"{s}\"");
+        let (prefix, id) = match s.rsplit_once('-') {
+            Some((prefix, id)) if is_valid_prefix(prefix) => (Some(prefix.to_uppercase()), id),
+            Some(_) => return Err(invalid()),
+            None => (None, s),
+        };
+        let id = id.parse::<u32>().map_err(|_| invalid())?;
+        Ok(ItemKey { prefix, id })
+    }
+}
+
+/// Turns the numeric ids items are stored under into the keys shown to users, and back.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct KeyPrefix(Option<String>);
+
+impl KeyPrefix {
+    pub fn new(prefix: &str) -> anyhow::Result<Self> {
+        if !is_valid_prefix(prefix) {
+            return Err(anyhow::anyhow!(
+                "Invalid key prefix \"{prefix}\", expected letters and digits starting with a letter"
+            ));
+        }
+        Ok(KeyPrefix(Some(prefix.to_uppercase())))
+    }
+
+    /// `PAY-17`, or the bare id without a prefix.
+    pub fn key(&self, id: u32) -> String {
+        match &self.0 {
+            Some(prefix) => format!("{prefix}-{id}"),
+            None => id.to_string(),
+        }
+    }
+
+    /// Bare ids are always accepted, keys only with this project's prefix.
+    pub fn resolve(&self, key: &ItemKey) -> anyhow::Result<u32> {
+        match (&key.prefix, &self.0) {
+            (None, _) => Ok(key.id),
+            (Some(prefix), Some(own_prefix)) if prefix == own_prefix => Ok(key.id),
+            (Some(prefix), _) => Err(anyhow::anyhow!(
+                "{prefix}-{} is not a key of this project",
+                key.id
+            )),
+        }
+    }
+
+    /// The id `input` refers to, if it is a key or id at all.
+    pub fn parse(&self, input: &str) -> Option<u32> {
+        let key = input.parse::<ItemKey>().ok()?;
+        self.resolve(&key).ok()
+    }
+}
+
+fn is_valid_prefix(prefix: &str) -> bool {
+    prefix.starts_with(|c: char| c.is_ascii_alphabetic())
+        && prefix.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// A single edit of an epic or story field, kept for the item's history.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct FieldChange {
+    pub at: DateTime<Utc>,
+    pub author: String,
+    pub field: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl FieldChange {
+    pub fn new(field: &str, from: String, to: String, author: &str) -> FieldChange {
+        FieldChange {
+            at: Utc::now(),
+            author: author.to_owned(),
+            field: field.to_owned(),
+            from,
+            to,
+        }
+    }
+}
+
+// Fields added after the first release are `#[serde(default)]`: events logged before
+// they existed still have to replay.
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
 pub struct Epic {
     pub name: String,
     pub description: String,
     pub status: Status,
+    #[serde(default)]
+    pub priority: Priority,
+    /// Username of the owner, from the board's users.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Tags like `backend` or `tech-debt`, for grouping work across epics.
+    #[serde(default)]
+    pub labels: BTreeSet<String>,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
     pub stories: Vec<u32>,
+    /// `None` for items created before timestamps were tracked.
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Set by `JiraDatabase` when the item is stored, to its current user.
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub history: Vec<FieldChange>,
 }
 
 impl Epic {
     pub fn new(name: String, description: String) -> Epic {
+        let now = Utc::now();
         Epic {
             name,
             description,
             status: Status::Open,
+            priority: Priority::default(),
+            owner: None,
+            labels: BTreeSet::new(),
+            due_date: None,
             stories: Vec::new(),
+            created_at: Some(now),
+            created_by: None,
+            updated_at: Some(now),
+            history: Vec::new(),
         }
     }
+
+    pub fn record_change(&mut self, change: FieldChange) {
+        self.updated_at = Some(change.at);
+        self.history.push(change);
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Clone, Eq, Debug)]
@@ -85,21 +400,306 @@ pub struct Story {
     pub name: String,
     pub description: String,
     pub status: Status,
+    #[serde(default)]
+    pub priority: Priority,
+    /// Username of the assignee, from the board's users.
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeSet<String>,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    /// The discussion thread, oldest first.
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    /// `None` for items created before timestamps were tracked.
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Set by `JiraDatabase` when the item is stored, to its current user.
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub history: Vec<FieldChange>,
 }
 
 impl Story {
     pub fn new(name: String, description: String) -> Story {
+        let now = Utc::now();
         Story {
             name,
             description,
             status: Status::Open,
+            priority: Priority::default(),
+            assignee: None,
+            labels: BTreeSet::new(),
+            due_date: None,
+            comments: Vec::new(),
+            created_at: Some(now),
+            created_by: None,
+            updated_at: Some(now),
+            history: Vec::new(),
         }
     }
+
+    pub fn record_change(&mut self, change: FieldChange) {
+        self.updated_at = Some(change.at);
+        self.history.push(change);
+    }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Clone, Eq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct Comment {
+    /// Numbers the comments of a story, `#1` is the first one.
+    pub id: u32,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    /// `None` until the comment is edited.
+    #[serde(default)]
+    pub edited_at: Option<DateTime<Utc>>,
+    pub body: String,
+}
+
+/// A deleted epic, with its story list as it was when deleted.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct TrashedEpic {
+    pub epic: Epic,
+    pub deleted_at: DateTime<Utc>,
+    pub deleted_by: String,
+}
+
+/// A deleted story and the epic it gets linked back into when restored.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct TrashedStory {
+    pub story: Story,
+    pub epic_id: u32,
+    pub deleted_at: DateTime<Utc>,
+    pub deleted_by: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct Trash {
+    pub epics: HashMap<u32, TrashedEpic>,
+    pub stories: HashMap<u32, TrashedStory>,
+}
+
+impl Trash {
+    pub fn is_empty(&self) -> bool {
+        self.epics.is_empty() && self.stories.is_empty()
+    }
+
+    /// Permanently drops everything deleted before `cutoff`.
+    pub fn purge_deleted_before(&mut self, cutoff: DateTime<Utc>) {
+        self.epics.retain(|_, trashed| trashed.deleted_at >= cutoff);
+        self.stories
+            .retain(|_, trashed| trashed.deleted_at >= cutoff);
+    }
+}
+
+/// Someone on the board, known by a username like `alice`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct User {
+    /// Full name, may be empty.
+    #[serde(default)]
+    pub name: String,
+}
+
+impl User {
+    /// Usernames are a single word, `alice` or `bob.smith`.
+    pub fn validate_username(username: &str) -> anyhow::Result<()> {
+        if username.is_empty() || username.chars().any(char::is_whitespace) {
+            return Err(anyhow::anyhow!(
+                "Invalid username \"{username}\", it can't be empty or contain spaces"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Eq, Debug, Default)]
 pub struct DBState {
+    pub revision: u64,
     pub last_item_id: u32,
     pub epics: HashMap<u32, Epic>,
     pub stories: HashMap<u32, Story>,
+    #[serde(default)]
+    pub trash: Trash,
+    /// Who items can be assigned to, by username.
+    #[serde(default)]
+    pub users: BTreeMap<String, User>,
+}
+
+impl DBState {
+    /// Every label used on the board, trashed items aside.
+    pub fn labels(&self) -> BTreeSet<String> {
+        let epic_labels = self.epics.values().flat_map(|epic| &epic.labels);
+        let story_labels = self.stories.values().flat_map(|story| &story.labels);
+        epic_labels.chain(story_labels).cloned().collect()
+    }
+
+    /// The epic listing the story.
+    pub fn epic_of(&self, story_id: u32) -> Option<u32> {
+        self.epics
+            .iter()
+            .find(|(_, epic)| epic.stories.contains(&story_id))
+            .map(|(epic_id, _)| *epic_id)
+    }
+}
+
+/// Past its due date and neither resolved nor closed.
+pub fn is_overdue(due_date: Option<NaiveDate>, status: &Status, today: NaiveDate) -> bool {
+    !status.is_done() && due_date.is_some_and(|due_date| due_date < today)
+}
+
+/// Accepts an ISO date (`2023-05-19`), `today`, `tomorrow`, days or weeks from `today`
+/// (`+3d`, `+2w`) and weekdays (`friday` or `next friday`, the first one after `today`).
+pub fn parse_due_date(input: &str, today: NaiveDate) -> anyhow::Result<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid date \"{input}\", expected a date like 2023-05-19, +3d, +2w or next friday"
+        )
+    };
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    let days = match input.as_str() {
+        "today" => 0,
+        "tomorrow" => 1,
+        _ => match input.strip_prefix('+') {
+            Some(offset) => match (offset.strip_suffix('d'), offset.strip_suffix('w')) {
+                (Some(days), _) => days.parse::<u64>().map_err(|_| invalid())?,
+                (_, Some(weeks)) => weeks.parse::<u64>().map_err(|_| invalid())? * 7,
+                _ => return Err(invalid()),
+            },
+            None => {
+                let weekday = input.strip_prefix("next ").unwrap_or(&input);
+                let weekday = weekday.parse::<Weekday>().map_err(|_| invalid())?;
+                let ahead =
+                    7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday();
+                match ahead % 7 {
+                    0 => 7,
+                    ahead => ahead as u64,
+                }
+            }
+        },
+    };
+    today.checked_add_days(Days::new(days)).ok_or_else(invalid)
+}
+
+/// Labels are a single word, `backend` or `customer-x`.
+pub fn validate_label(label: &str) -> anyhow::Result<()> {
+    if label.is_empty() || label.chars().any(|c| c.is_whitespace() || c == ',') {
+        return Err(anyhow::anyhow!(
+            "Invalid label \"{label}\", it can't be empty or contain spaces or commas"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_should_parse_names_and_sort_by_urgency() {
+        assert_eq!("highest".parse::<Priority>().unwrap(), Priority::Highest);
+        assert_eq!("Low".parse::<Priority>().unwrap(), Priority::Low);
+        assert!("urgent".parse::<Priority>().is_err());
+
+        let mut priorities = vec![Priority::Low, Priority::Highest, Priority::Medium];
+        priorities.sort();
+        assert_eq!(
+            priorities,
+            vec![Priority::Highest, Priority::Medium, Priority::Low]
+        );
+    }
+
+    #[test]
+    fn labels_should_be_single_words_collected_across_the_board() {
+        assert!(validate_label("tech-debt").is_ok());
+        assert!(validate_label("").is_err());
+        assert!(validate_label("tech debt").is_err());
+        assert!(validate_label("a,b").is_err());
+
+        let mut db_state = DBState::default();
+        let mut epic = Epic::new("".to_owned(), "".to_owned());
+        epic.labels.insert("backend".to_owned());
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        story.labels.insert("api".to_owned());
+        story.labels.insert("backend".to_owned());
+        db_state.epics.insert(1, epic);
+        db_state.stories.insert(2, story);
+        assert_eq!(
+            db_state.labels().into_iter().collect::<Vec<_>>(),
+            vec!["api", "backend"]
+        );
+    }
+
+    #[test]
+    fn parse_due_date_should_accept_iso_and_relative_dates() {
+        // A wednesday.
+        let today = NaiveDate::from_ymd_opt(2023, 5, 17).unwrap();
+        let date = |input| parse_due_date(input, today).unwrap().to_string();
+
+        assert_eq!(date("2023-06-01"), "2023-06-01");
+        assert_eq!(date("today"), "2023-05-17");
+        assert_eq!(date("Tomorrow"), "2023-05-18");
+        assert_eq!(date("+3d"), "2023-05-20");
+        assert_eq!(date("+2w"), "2023-05-31");
+        assert_eq!(date("friday"), "2023-05-19");
+        assert_eq!(date("next friday"), "2023-05-19");
+        assert_eq!(date("next wed"), "2023-05-24");
+        assert!(parse_due_date("+3x", today).is_err());
+        assert!(parse_due_date("someday", today).is_err());
+        assert!(parse_due_date("2023-02-30", today).is_err());
+    }
+
+    #[test]
+    fn is_overdue_should_ignore_done_items() {
+        let today = NaiveDate::from_ymd_opt(2023, 5, 17).unwrap();
+        let yesterday = NaiveDate::from_ymd_opt(2023, 5, 16);
+
+        assert!(is_overdue(yesterday, &Status::InProgress, today));
+        assert!(!is_overdue(yesterday, &Status::Resolved, today));
+        assert!(!is_overdue(Some(today), &Status::Open, today));
+        assert!(!is_overdue(None, &Status::Open, today));
+    }
+
+    #[test]
+    fn item_key_should_parse_keys_and_ids() {
+        let key = "pay-17".parse::<ItemKey>().unwrap();
+        assert_eq!(
+            key,
+            ItemKey {
+                prefix: Some("PAY".to_owned()),
+                id: 17
+            }
+        );
+        assert_eq!("17".parse::<ItemKey>().unwrap().prefix, None);
+
+        assert!("".parse::<ItemKey>().is_err());
+        assert!("PAY-".parse::<ItemKey>().is_err());
+        assert!("-17".parse::<ItemKey>().is_err());
+        assert!("1PAY-17".parse::<ItemKey>().is_err());
+        assert!("PAY 17".parse::<ItemKey>().is_err());
+    }
+
+    #[test]
+    fn key_prefix_should_format_and_resolve_keys() {
+        let prefix = KeyPrefix::new("pay").unwrap();
+        assert_eq!(prefix.key(17), "PAY-17");
+        assert_eq!(prefix.parse("PAY-17"), Some(17));
+        assert_eq!(prefix.parse("17"), Some(17));
+        assert_eq!(prefix.parse("OPS-17"), None);
+
+        let no_prefix = KeyPrefix::default();
+        assert_eq!(no_prefix.key(17), "17");
+        assert_eq!(no_prefix.parse("PAY-17"), None);
+
+        assert!(KeyPrefix::new("PAY-1").is_err());
+        assert!(KeyPrefix::new("").is_err());
+    }
 }
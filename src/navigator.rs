@@ -1,73 +1,241 @@
 use crate::{
-    db::JiraDatabase,
-    models::Action,
-    ui::{EpicDetail, HomePage, Page, Prompts, StoryDetail},
+    config::{self, Config},
+    db::{ConflictError, JiraDatabase},
+    models::{Action, StoryRank},
+    ui::{
+        Board, Deadlines, EpicDetail, HistoryItem, HomePage, ItemHistory, MyWork, Page,
+        ProjectPicker, Prompts, StoryDetail, TrashBin,
+    },
+    undo::UndoHistory,
 };
 use anyhow::{anyhow, Context, Result};
-use std::rc::Rc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+type Render = dyn Fn(&dyn Page) -> Result<()>;
 
 pub struct Navigator {
     pages: Vec<Box<dyn Page>>,
     prompts: Prompts,
-    db: Rc<JiraDatabase>,
+    /// Draws a page again after a conflict, the way the running interface draws pages.
+    render: Box<Render>,
+    undo_history: UndoHistory,
+    /// `None` until a project is picked.
+    db: Option<Rc<JiraDatabase>>,
+    config: Config,
 }
 
 impl Navigator {
     pub fn new(db: Rc<JiraDatabase>) -> Self {
         Self {
-            pages: vec![Box::new(HomePage { db: Rc::clone(&db) })],
+            pages: vec![Box::new(HomePage::new(Rc::clone(&db)))],
+            prompts: Prompts::new(),
+            render: Box::new(draw_plain),
+            undo_history: UndoHistory::in_memory(),
+            db: Some(db),
+            config: Config::default(),
+        }
+    }
+
+    /// Starts on the project picker, the picked project's board opens on top of it.
+    pub fn with_project_picker(config: Config, projects: Vec<String>) -> Self {
+        Self {
+            pages: vec![Box::new(ProjectPicker { projects })],
             prompts: Prompts::new(),
-            db,
+            render: Box::new(draw_plain),
+            undo_history: UndoHistory::in_memory(),
+            db: None,
+            config,
         }
     }
 
-    pub fn get_current_page(&self) -> Option<&Box<dyn Page>> {
-        self.pages.last()
+    pub fn set_undo_history(&mut self, undo_history: UndoHistory) {
+        self.undo_history = undo_history;
     }
 
+    pub fn set_render(&mut self, render: impl Fn(&dyn Page) -> Result<()> + 'static) {
+        self.render = Box::new(render);
+    }
+
+    pub fn get_current_page(&self) -> Option<&dyn Page> {
+        self.pages.last().map(|page| page.as_ref())
+    }
+
+    /// Handles the action. When a teammate changed the board underneath the page it was
+    /// dispatched from, an action that prompts is asked again over the redrawn page. Any
+    /// other action fails with the conflict, as it would otherwise apply to changes the
+    /// user hasn't seen.
     pub fn handle_action(&mut self, action: Action) -> Result<()> {
+        loop {
+            if let Some(db) = &self.db {
+                db.take_last_write();
+            }
+            match self.dispatch_action(action.clone()) {
+                Ok(()) => {
+                    let last_write = self.db.as_ref().and_then(|db| db.take_last_write());
+                    if let Some((before, after)) = last_write {
+                        self.undo_history
+                            .record(action.to_string(), before, after)?;
+                    }
+                    return Ok(());
+                }
+                Err(err) if err.downcast_ref::<ConflictError>().is_some() && prompts(&action) => {
+                    self.redraw_current_page()?;
+                    println!("{:#}. Showing the latest data, please try again.", err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn dispatch_action(&mut self, action: Action) -> Result<()> {
         match action {
             Action::NavigateToEpicDetail { epic_id } => {
-                let epic_details_page = Box::new(EpicDetail {
-                    db: Rc::clone(&self.db),
-                    epic_id,
-                });
+                let epic_details_page = Box::new(EpicDetail::new(self.db()?, epic_id));
 
                 self.pages.push(epic_details_page);
             }
             Action::NavigateToStoryDetail { epic_id, story_id } => {
                 let story_details_page = Box::new(StoryDetail {
-                    db: Rc::clone(&self.db),
+                    db: self.db()?,
                     epic_id,
                     story_id,
                 });
 
                 self.pages.push(story_details_page);
             }
+            Action::NavigateToEpicHistory { epic_id } => {
+                self.pages.push(Box::new(ItemHistory {
+                    item: HistoryItem::Epic(epic_id),
+                    db: self.db()?,
+                }));
+            }
+            Action::NavigateToStoryHistory { story_id } => {
+                self.pages.push(Box::new(ItemHistory {
+                    item: HistoryItem::Story(story_id),
+                    db: self.db()?,
+                }));
+            }
+            Action::NavigateToTrash => {
+                self.pages.push(Box::new(TrashBin { db: self.db()? }));
+            }
+            Action::NavigateToMyWork => {
+                self.pages.push(Box::new(MyWork { db: self.db()? }));
+            }
+            Action::NavigateToDeadlines => {
+                self.pages.push(Box::new(Deadlines::new(self.db()?)));
+            }
+            Action::NavigateToBoard { epic_id } => {
+                self.pages.push(Box::new(Board::new(self.db()?, epic_id)));
+            }
             Action::NavigateToPreviousPage => {
                 if !self.pages.is_empty() {
                     self.pages.pop();
                 }
             }
+            Action::OpenProject { name } => {
+                let project = self.config.project(&name)?;
+                let db = Rc::new(self.config.open_database(&project)?);
+                self.undo_history = UndoHistory::load(config::undo_history_path(&project.db))?;
+                self.pages.push(Box::new(HomePage::new(Rc::clone(&db))));
+                self.db = Some(db);
+            }
             Action::CreateEpic => {
                 let epic = (self.prompts.create_epic)();
-                self.db
+                self.db()?
                     .create_epic(epic)
                     .with_context(|| anyhow!("Failed to create epic"))?;
             }
+            Action::UpdateEpicName { epic_id } => {
+                let db = self.db()?;
+                let epic = db
+                    .snapshot()?
+                    .epics
+                    .remove(&epic_id)
+                    .ok_or_else(|| anyhow!("Epic with {epic_id} not found"))?;
+                if let Some(name) = (self.prompts.update_name)(&epic.name) {
+                    db.update_epic_name(epic_id, &name)
+                        .with_context(|| anyhow!("Failed to rename epic"))?;
+                }
+            }
+            Action::UpdateEpicDescription { epic_id } => {
+                let db = self.db()?;
+                let epic = db
+                    .snapshot()?
+                    .epics
+                    .remove(&epic_id)
+                    .ok_or_else(|| anyhow!("Epic with {epic_id} not found"))?;
+                if let Some(description) = (self.prompts.update_description)(&epic.description) {
+                    db.update_epic_description(epic_id, &description)
+                        .with_context(|| anyhow!("Failed to update epic description"))?;
+                }
+            }
             Action::UpdateEpicStatus { epic_id } => {
                 let epic_status = (self.prompts.update_status)();
                 if let Some(status) = epic_status {
-                    self.db
+                    self.db()?
                         .update_epic_status(epic_id, status)
                         .with_context(|| anyhow!("Failed to update epic status"))?;
                 }
             }
+            Action::UpdateEpicPriority { epic_id } => {
+                if let Some(priority) = (self.prompts.update_priority)() {
+                    self.db()?
+                        .update_epic_priority(epic_id, priority)
+                        .with_context(|| anyhow!("Failed to update epic priority"))?;
+                }
+            }
+            Action::UpdateEpicOwner { epic_id } => {
+                if let Some(owner) = self.pick_user()? {
+                    self.db()?
+                        .update_epic_owner(epic_id, Some(owner))
+                        .with_context(|| anyhow!("Failed to update epic owner"))?;
+                }
+            }
+            Action::RemoveEpicOwner { epic_id } => {
+                self.db()?
+                    .update_epic_owner(epic_id, None)
+                    .with_context(|| anyhow!("Failed to remove epic owner"))?;
+            }
+            Action::AddEpicLabel { epic_id } => {
+                if let Some(label) = (self.prompts.add_label)() {
+                    self.db()?
+                        .add_epic_label(epic_id, &label)
+                        .with_context(|| anyhow!("Failed to add epic label"))?;
+                }
+            }
+            Action::RemoveEpicLabel { epic_id } => {
+                let db = self.db()?;
+                let labels = db
+                    .snapshot()?
+                    .epics
+                    .get(&epic_id)
+                    .map(|epic| epic.labels.clone())
+                    .unwrap_or_default();
+                if let Some(label) = self.pick_label(&labels)? {
+                    db.remove_epic_label(epic_id, &label)
+                        .with_context(|| anyhow!("Failed to remove epic label"))?;
+                }
+            }
+            Action::UpdateEpicDueDate { epic_id } => {
+                if let Some(due_date) = (self.prompts.update_due_date)() {
+                    self.db()?
+                        .update_epic_due_date(epic_id, Some(due_date))
+                        .with_context(|| anyhow!("Failed to update epic due date"))?;
+                }
+            }
+            Action::RemoveEpicDueDate { epic_id } => {
+                self.db()?
+                    .update_epic_due_date(epic_id, None)
+                    .with_context(|| anyhow!("Failed to remove epic due date"))?;
+            }
             Action::DeleteEpic { epic_id } => {
                 let should_delete_epic = (self.prompts.delete_epic)();
 
                 if should_delete_epic {
-                    self.db
+                    self.db()?
                         .delete_epic(epic_id)
                         .with_context(|| anyhow!("Failed to delete epic"))?;
 
@@ -78,22 +246,161 @@ impl Navigator {
             }
             Action::CreateStory { epic_id } => {
                 let story = (self.prompts.create_story)();
-                self.db
+                self.db()?
                     .create_story(story, epic_id)
                     .with_context(|| anyhow!("Failed to create story"))?;
             }
-            Action::UpdateStoryStatus { story_id } => {
-                let status = (self.prompts.update_status)();
+            Action::UpdateStoryName { story_id } => {
+                let db = self.db()?;
+                let story = db
+                    .snapshot()?
+                    .stories
+                    .remove(&story_id)
+                    .ok_or_else(|| anyhow!("Story with {story_id} not found"))?;
+                if let Some(name) = (self.prompts.update_name)(&story.name) {
+                    db.update_story_name(story_id, &name)
+                        .with_context(|| anyhow!("Failed to rename story"))?;
+                }
+            }
+            Action::UpdateStoryDescription { story_id } => {
+                let db = self.db()?;
+                let story = db
+                    .snapshot()?
+                    .stories
+                    .remove(&story_id)
+                    .ok_or_else(|| anyhow!("Story with {story_id} not found"))?;
+                if let Some(description) = (self.prompts.update_description)(&story.description) {
+                    db.update_story_description(story_id, &description)
+                        .with_context(|| anyhow!("Failed to update story description"))?;
+                }
+            }
+            Action::UpdateStoryStatus { story_id, to } => {
+                // Moves on the board give the status, the story's page prompts for it.
+                let status = to.or_else(|| (self.prompts.update_status)());
                 if let Some(status) = status {
-                    self.db
+                    self.db()?
                         .update_story_status(story_id, status)
                         .with_context(|| anyhow!("Failed to update story status!"))?;
                 }
             }
+            Action::UpdateStoryPriority { story_id } => {
+                if let Some(priority) = (self.prompts.update_priority)() {
+                    self.db()?
+                        .update_story_priority(story_id, priority)
+                        .with_context(|| anyhow!("Failed to update story priority"))?;
+                }
+            }
+            Action::AssignStory { story_id } => {
+                if let Some(assignee) = self.pick_user()? {
+                    self.db()?
+                        .assign_story(story_id, Some(assignee))
+                        .with_context(|| anyhow!("Failed to assign story"))?;
+                }
+            }
+            Action::UnassignStory { story_id } => {
+                self.db()?
+                    .assign_story(story_id, None)
+                    .with_context(|| anyhow!("Failed to unassign story"))?;
+            }
+            Action::AddStoryLabel { story_id } => {
+                if let Some(label) = (self.prompts.add_label)() {
+                    self.db()?
+                        .add_story_label(story_id, &label)
+                        .with_context(|| anyhow!("Failed to add story label"))?;
+                }
+            }
+            Action::RemoveStoryLabel { story_id } => {
+                let db = self.db()?;
+                let labels = db
+                    .snapshot()?
+                    .stories
+                    .get(&story_id)
+                    .map(|story| story.labels.clone())
+                    .unwrap_or_default();
+                if let Some(label) = self.pick_label(&labels)? {
+                    db.remove_story_label(story_id, &label)
+                        .with_context(|| anyhow!("Failed to remove story label"))?;
+                }
+            }
+            Action::UpdateStoryDueDate { story_id } => {
+                if let Some(due_date) = (self.prompts.update_due_date)() {
+                    self.db()?
+                        .update_story_due_date(story_id, Some(due_date))
+                        .with_context(|| anyhow!("Failed to update story due date"))?;
+                }
+            }
+            Action::RemoveStoryDueDate { story_id } => {
+                self.db()?
+                    .update_story_due_date(story_id, None)
+                    .with_context(|| anyhow!("Failed to remove story due date"))?;
+            }
+            Action::CommentOnStory { story_id } => {
+                if let Some(body) = (self.prompts.write_comment)(None) {
+                    self.db()?
+                        .add_comment(story_id, &body)
+                        .with_context(|| anyhow!("Failed to add comment"))?;
+                }
+            }
+            Action::EditComment { story_id } => {
+                let db = self.db()?;
+                if let Some((comment_id, body)) = self.pick_own_comment(story_id)? {
+                    if let Some(body) = (self.prompts.write_comment)(Some(&body)) {
+                        db.edit_comment(story_id, comment_id, &body)
+                            .with_context(|| anyhow!("Failed to edit comment"))?;
+                    }
+                }
+            }
+            Action::DeleteComment { story_id } => {
+                if let Some((comment_id, _)) = self.pick_own_comment(story_id)? {
+                    self.db()?
+                        .delete_comment(story_id, comment_id)
+                        .with_context(|| anyhow!("Failed to delete comment"))?;
+                }
+            }
+            Action::MoveStory { story_id } => {
+                let db = self.db()?;
+                let db_state = db.snapshot()?;
+                let epics = db_state
+                    .epics
+                    .iter()
+                    .filter(|(_, epic)| !epic.stories.contains(&story_id))
+                    .map(|(epic_id, epic)| (*epic_id, epic.name.clone()))
+                    .collect::<BTreeMap<_, _>>();
+                if epics.is_empty() {
+                    return Err(anyhow!("There is no other epic to move the story to"));
+                }
+
+                if let Some(epic_id) = (self.prompts.pick_epic)(db.key_prefix(), &epics) {
+                    db.move_story(story_id, epic_id)
+                        .with_context(|| anyhow!("Failed to move story"))?;
+
+                    // The story's page now belongs to the new epic.
+                    let on_story_page = self.get_current_page().is_some_and(|page| {
+                        page.as_any()
+                            .downcast_ref::<StoryDetail>()
+                            .is_some_and(|page| page.story_id == story_id)
+                    });
+                    if on_story_page {
+                        self.pages.pop();
+                        self.pages.push(Box::new(StoryDetail {
+                            epic_id,
+                            story_id,
+                            db,
+                        }));
+                    }
+                }
+            }
+            Action::MoveStoryUp { story_id } => self.rank_story(story_id, StoryRank::Up)?,
+            Action::MoveStoryDown { story_id } => self.rank_story(story_id, StoryRank::Down)?,
+            Action::MoveStoryToTop { story_id } => self.rank_story(story_id, StoryRank::Top)?,
+            Action::RankStoryBefore {
+                story_id,
+                before_id,
+            } => self.rank_story(story_id, StoryRank::Before(before_id))?,
             Action::DeleteStory { epic_id, story_id } => {
                 let ok = (self.prompts.delete_story)();
                 if ok {
-                    self.db
+                    self.db()?
                         .delete_story(epic_id, story_id)
                         .with_context(|| anyhow!("Failed to delete story"))?;
 
@@ -102,37 +409,218 @@ impl Navigator {
                     }
                 }
             }
+            Action::RestoreEpic { epic_id } => {
+                self.db()?
+                    .restore_epic(epic_id)
+                    .with_context(|| anyhow!("Failed to restore epic"))?;
+            }
+            Action::RestoreStory { story_id } => {
+                self.db()?
+                    .restore_story(story_id)
+                    .with_context(|| anyhow!("Failed to restore story"))?;
+            }
+            Action::PurgeEpic { epic_id } => {
+                if (self.prompts.purge_item)() {
+                    self.db()?
+                        .purge_epic(epic_id)
+                        .with_context(|| anyhow!("Failed to purge epic"))?;
+                }
+            }
+            Action::PurgeStory { story_id } => {
+                if (self.prompts.purge_item)() {
+                    self.db()?
+                        .purge_story(story_id)
+                        .with_context(|| anyhow!("Failed to purge story"))?;
+                }
+            }
+            Action::FilterByLabel => {
+                // Picking nothing clears the filter, so this prompts even without labels.
+                let label = (self.prompts.pick_label)(&self.db()?.snapshot()?.labels());
+                if let Some(page) = self.pages.last() {
+                    let page = page.as_any();
+                    if let Some(home_page) = page.downcast_ref::<HomePage>() {
+                        home_page.filter_by_label(label);
+                    } else if let Some(epic_detail) = page.downcast_ref::<EpicDetail>() {
+                        epic_detail.filter_by_label(label);
+                    }
+                }
+            }
+            Action::Undo => {
+                let db = self.db()?;
+                self.undo_history.undo(&db)?;
+                self.close_pages_of_missing_items()?;
+            }
+            Action::Redo => {
+                let db = self.db()?;
+                self.undo_history.redo(&db)?;
+                self.close_pages_of_missing_items()?;
+            }
             Action::Exit => self.pages.clear(),
         }
         Ok(())
     }
 
+    /// Undo and redo can remove the epic or story an open page shows, so those pages
+    /// (and everything above them) are closed.
+    fn close_pages_of_missing_items(&mut self) -> Result<()> {
+        let db_state = self.db()?.read()?;
+        let first_missing = self.pages.iter().position(|page| {
+            let page = page.as_any();
+            if let Some(epic_detail) = page.downcast_ref::<EpicDetail>() {
+                return !db_state.epics.contains_key(&epic_detail.epic_id);
+            }
+            if let Some(story_detail) = page.downcast_ref::<StoryDetail>() {
+                // Undoing a move takes the story out of the page's epic.
+                return db_state.epic_of(story_detail.story_id) != Some(story_detail.epic_id);
+            }
+            match page
+                .downcast_ref::<ItemHistory>()
+                .map(|history| history.item)
+            {
+                Some(HistoryItem::Epic(epic_id)) => !db_state.epics.contains_key(&epic_id),
+                Some(HistoryItem::Story(story_id)) => !db_state.stories.contains_key(&story_id),
+                None => false,
+            }
+        });
+
+        if let Some(index) = first_missing {
+            self.pages.truncate(index);
+        }
+        Ok(())
+    }
+
+    fn rank_story(&self, story_id: u32, rank: StoryRank) -> Result<()> {
+        self.db()?
+            .rank_story(story_id, rank)
+            .with_context(|| anyhow!("Failed to rank story"))
+    }
+
+    fn pick_user(&self) -> Result<Option<String>> {
+        let users = self.db()?.snapshot()?.users;
+        if users.is_empty() {
+            return Err(anyhow!(
+                "This board has no users yet, add them with the `user add` command"
+            ));
+        }
+        Ok((self.prompts.pick_user)(&users))
+    }
+
+    fn pick_label(&self, labels: &BTreeSet<String>) -> Result<Option<String>> {
+        if labels.is_empty() {
+            return Err(anyhow!("There are no labels to pick from"));
+        }
+        Ok((self.prompts.pick_label)(labels))
+    }
+
+    /// The id and text of one of the current user's comments on the story.
+    fn pick_own_comment(&self, story_id: u32) -> Result<Option<(u32, String)>> {
+        let db = self.db()?;
+        let comments = db
+            .snapshot()?
+            .stories
+            .get(&story_id)
+            .map(|story| story.comments.clone())
+            .unwrap_or_default();
+        let own_comments = comments
+            .into_iter()
+            .filter(|comment| comment.author == db.current_user())
+            .collect::<Vec<_>>();
+        if own_comments.is_empty() {
+            return Err(anyhow!("You haven't commented on this story"));
+        }
+
+        let comment_ids = own_comments
+            .iter()
+            .map(|comment| comment.id)
+            .collect::<Vec<_>>();
+        let picked = (self.prompts.pick_comment)(&comment_ids);
+        Ok(own_comments
+            .into_iter()
+            .find(|comment| Some(comment.id) == picked)
+            .map(|comment| (comment.id, comment.body)))
+    }
+
+    fn db(&self) -> Result<Rc<JiraDatabase>> {
+        self.db
+            .clone()
+            .ok_or_else(|| anyhow!("Pick a project first"))
+    }
+
+    fn redraw_current_page(&self) -> Result<()> {
+        match self.get_current_page() {
+            Some(page) => (self.render)(page),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(test)]
     fn get_page_count(&self) -> usize {
         self.pages.len()
     }
 
+    #[cfg(test)]
     fn set_prompts(&mut self, prompts: Prompts) {
         self.prompts = prompts
     }
 
+    #[cfg(test)]
     fn add_page(&mut self, page: Box<dyn Page>) {
         self.pages.push(page);
     }
 }
 
+fn draw_plain(page: &dyn Page) -> Result<()> {
+    clearscreen::clear()?;
+    page.draw_page()
+}
+
+/// Whether the action asks the user for input, who then sees the data it acts on.
+fn prompts(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::CreateEpic
+            | Action::UpdateEpicName { .. }
+            | Action::UpdateEpicDescription { .. }
+            | Action::UpdateEpicStatus { .. }
+            | Action::UpdateEpicPriority { .. }
+            | Action::UpdateEpicOwner { .. }
+            | Action::AddEpicLabel { .. }
+            | Action::RemoveEpicLabel { .. }
+            | Action::UpdateEpicDueDate { .. }
+            | Action::DeleteEpic { .. }
+            | Action::CreateStory { .. }
+            | Action::UpdateStoryName { .. }
+            | Action::UpdateStoryDescription { .. }
+            | Action::UpdateStoryStatus { to: None, .. }
+            | Action::UpdateStoryPriority { .. }
+            | Action::AssignStory { .. }
+            | Action::AddStoryLabel { .. }
+            | Action::RemoveStoryLabel { .. }
+            | Action::UpdateStoryDueDate { .. }
+            | Action::CommentOnStory { .. }
+            | Action::EditComment { .. }
+            | Action::DeleteComment { .. }
+            | Action::MoveStory { .. }
+            | Action::DeleteStory { .. }
+            | Action::PurgeEpic { .. }
+            | Action::PurgeStory { .. }
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::NaiveDate;
+    use std::cell::Cell;
+
     use crate::{
         db::test_utils::MockDB,
-        models::{Epic, Status, Story},
+        models::{Epic, Priority, Status, Story, User},
     };
 
     #[test]
     fn should_start_on_home_page() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let nav = Navigator::new(db);
 
         assert_eq!(nav.get_page_count(), 1);
@@ -140,14 +628,49 @@ mod tests {
         let current_page = nav.get_current_page().unwrap();
         let home_page = current_page.as_any().downcast_ref::<HomePage>();
 
-        assert_eq!(home_page.is_some(), true);
+        assert!(home_page.is_some());
+    }
+
+    #[test]
+    fn handle_action_should_open_the_picked_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let config: Config = toml::from_str(&format!(
+            "[projects.payments]\ndb = {:?}\n",
+            dir.path().join("payments.json")
+        ))
+        .unwrap();
+        let mut prompts = Prompts::new();
+        prompts.create_epic = Box::new(|| Epic::new("name".to_owned(), "".to_owned()));
+        let mut nav = Navigator::with_project_picker(config, vec!["payments".to_owned()]);
+        nav.set_prompts(prompts);
+        let current_page = nav.get_current_page().unwrap();
+        assert!(current_page
+            .as_any()
+            .downcast_ref::<ProjectPicker>()
+            .is_some());
+        assert!(nav.handle_action(Action::CreateEpic).is_err());
+
+        nav.handle_action(Action::OpenProject {
+            name: "payments".to_owned(),
+        })
+        .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        assert!(current_page.as_any().downcast_ref::<HomePage>().is_some());
+        assert_eq!(nav.get_page_count(), 2);
+
+        nav.handle_action(Action::CreateEpic).unwrap();
+        assert!(dir.path().join("payments.json.undo.json").exists());
+
+        assert!(nav
+            .handle_action(Action::OpenProject {
+                name: "unknown".to_owned(),
+            })
+            .is_err());
     }
 
     #[test]
     fn handle_action_should_navigate_pages() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let mut nav = Navigator::new(db);
 
         nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
@@ -187,11 +710,29 @@ mod tests {
         assert!(nav.get_current_page().is_none());
     }
 
+    #[test]
+    fn handle_action_should_navigate_to_history() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(db);
+
+        nav.handle_action(Action::NavigateToStoryHistory { story_id: 2 })
+            .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        let history = current_page.as_any().downcast_ref::<ItemHistory>().unwrap();
+        assert_eq!(history.item, HistoryItem::Story(2));
+
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+        nav.handle_action(Action::NavigateToEpicHistory { epic_id: 1 })
+            .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        let history = current_page.as_any().downcast_ref::<ItemHistory>().unwrap();
+        assert_eq!(history.item, HistoryItem::Epic(1));
+        assert_eq!(nav.get_page_count(), 2);
+    }
+
     #[test]
     fn handle_exit_action_should_clear_pages() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let mut nav = Navigator::new(db);
 
         nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
@@ -204,9 +745,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_create_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let mut nav = Navigator::new(Rc::clone(&db));
 
         let mut prompts = Prompts::new();
@@ -227,10 +766,43 @@ mod tests {
     }
 
     #[test]
-    fn handle_action_should_update_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
+    fn handle_action_should_prompt_again_on_conflict() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            tmpfile.path(),
+            r#"{ "schema_version": 8, "revision": 0, "last_item_id": 0, "epics": {}, "stories": {} }"#,
+        )
+        .unwrap();
+        let file_path = tmpfile.path().to_str().unwrap().to_string();
+        let db = Rc::new(JiraDatabase::new(file_path.clone()));
+        let teammate_db = JiraDatabase::new(file_path);
+
+        let prompt_count = Rc::new(std::cell::Cell::new(0));
+        let mut prompts = Prompts::new();
+        let counter = Rc::clone(&prompt_count);
+        prompts.create_epic = Box::new(move || {
+            counter.set(counter.get() + 1);
+            Epic::new("mine".to_owned(), "".to_owned())
         });
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+
+        db.read().unwrap();
+        teammate_db
+            .create_epic(Epic::new("theirs".to_owned(), "".to_owned()))
+            .unwrap();
+
+        nav.handle_action(Action::CreateEpic).unwrap();
+
+        assert_eq!(prompt_count.get(), 2);
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.epics.len(), 2);
+        assert_eq!(db_state.epics[&2].name, "mine");
+    }
+
+    #[test]
+    fn handle_action_should_update_epic() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic = Epic::new("name".to_owned(), "description".to_owned());
         let epic_id = db.create_epic(epic).unwrap();
         let db_state = db.read().unwrap();
@@ -257,46 +829,33 @@ mod tests {
 
     #[test]
     fn handle_action_should_delete_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic = Epic::new("name".to_owned(), "description".to_owned());
         let epic_id = db.create_epic(epic).unwrap();
 
         let mut prompts = Prompts::new();
         prompts.delete_epic = Box::new(|| true);
         let mut nav = Navigator::new(Rc::clone(&db));
-        nav.add_page(Box::new(EpicDetail {
-            db: Rc::clone(&db),
-            epic_id,
-        }));
+        nav.add_page(Box::new(EpicDetail::new(Rc::clone(&db), epic_id)));
         nav.set_prompts(prompts);
 
         let db_state = db.read().unwrap();
         assert_eq!(db_state.epics.len(), 1);
         let current_page = nav.get_current_page().unwrap();
-        assert_eq!(
-            current_page.as_any().downcast_ref::<EpicDetail>().is_some(),
-            true
-        );
+        assert!(current_page.as_any().downcast_ref::<EpicDetail>().is_some());
         assert_eq!(nav.pages.len(), 2);
 
         nav.handle_action(Action::DeleteEpic { epic_id }).unwrap();
         let current_page = nav.get_current_page().unwrap();
         assert_eq!(nav.pages.len(), 1);
-        assert_eq!(
-            current_page.as_any().downcast_ref::<EpicDetail>().is_some(),
-            false
-        );
+        assert!(current_page.as_any().downcast_ref::<EpicDetail>().is_none());
         let db_state = db.read().unwrap();
         assert!(db_state.epics.is_empty());
     }
 
     #[test]
     fn handle_action_should_create_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -314,7 +873,7 @@ mod tests {
         nav.handle_action(Action::CreateStory { epic_id }).unwrap();
 
         let db_state = db.read().unwrap();
-        assert_eq!(db_state.stories.is_empty(), false);
+        assert!(!db_state.stories.is_empty());
         let (story_id, story) = db_state.stories.iter().next().unwrap();
         assert_eq!(story.name, "name");
         assert_eq!(story.description, "description");
@@ -325,9 +884,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_update_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -345,7 +902,7 @@ mod tests {
         let mut nav = Navigator::new(Rc::clone(&db));
         nav.set_prompts(prompts);
 
-        nav.handle_action(Action::UpdateStoryStatus { story_id })
+        nav.handle_action(Action::UpdateStoryStatus { story_id, to: None })
             .unwrap();
 
         let db_state = db.read().unwrap();
@@ -356,10 +913,364 @@ mod tests {
     }
 
     #[test]
-    fn handle_action_should_delete_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
+    fn handle_action_should_move_stories_on_the_board() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let mut prompts = Prompts::new();
+        prompts.update_status = Box::new(|| panic!("moves on the board don't prompt"));
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToBoard {
+            epic_id: Some(epic_id),
+        })
+        .unwrap();
+        nav.handle_action(Action::UpdateStoryStatus {
+            story_id,
+            to: Some(Status::Resolved),
+        })
+        .unwrap();
+
+        assert_eq!(
+            db.read().unwrap().stories[&story_id].status,
+            Status::Resolved
+        );
+        let current_page = nav.get_current_page().unwrap();
+        let board = current_page.as_any().downcast_ref::<Board>().unwrap();
+        assert_eq!(board.epic_id, Some(epic_id));
+    }
+
+    #[test]
+    fn input_should_act_on_the_revision_the_page_was_drawn_at() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::NavigateToBoard { epic_id: None })
+            .unwrap();
+        nav.get_current_page().unwrap().draw_page().unwrap();
+
+        // A teammate resolves the story after the board was drawn.
+        let teammate_update = |status: Status| {
+            let mut db_state = db.snapshot().unwrap();
+            db_state.stories.get_mut(&story_id).unwrap().status = status;
+            db_state.revision += 1;
+            db.database.write(&db_state).unwrap();
+        };
+        teammate_update(Status::Resolved);
+
+        let action = nav
+            .get_current_page()
+            .unwrap()
+            .handle_input(&format!("> {story_id}"))
+            .unwrap()
+            .unwrap();
+        let error = nav.handle_action(action).unwrap_err();
+        assert!(error.downcast_ref::<ConflictError>().is_some());
+        assert_eq!(
+            db.read().unwrap().stories[&story_id].status,
+            Status::Resolved
+        );
+
+        // Prompting again over the redrawn board lets the user act on what they now see.
+        let redraws = Rc::new(Cell::new(0));
+        let prompted = Rc::new(Cell::new(0));
+        let mut prompts = Prompts::new();
+        prompts.update_status = Box::new({
+            let prompted = Rc::clone(&prompted);
+            move || {
+                prompted.set(prompted.get() + 1);
+                Some(Status::Closed)
+            }
+        });
+        nav.set_prompts(prompts);
+        nav.set_render({
+            let redraws = Rc::clone(&redraws);
+            move |page| {
+                redraws.set(redraws.get() + 1);
+                page.rows().map(drop)
+            }
+        });
+        teammate_update(Status::InProgress);
+        nav.handle_action(Action::UpdateStoryStatus { story_id, to: None })
+            .unwrap();
+        assert_eq!((redraws.get(), prompted.get()), (1, 2));
+        assert_eq!(db.read().unwrap().stories[&story_id].status, Status::Closed);
+    }
+
+    #[test]
+    fn handle_action_should_update_priorities() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut prompts = Prompts::new();
+        prompts.update_priority = Box::new(|| Some(Priority::Highest));
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateEpicPriority { epic_id })
+            .unwrap();
+        nav.handle_action(Action::UpdateStoryPriority { story_id })
+            .unwrap();
+
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.epics[&epic_id].priority, Priority::Highest);
+        assert_eq!(db_state.stories[&story_id].priority, Priority::Highest);
+    }
+
+    #[test]
+    fn handle_action_should_assign_picked_users() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut prompts = Prompts::new();
+        prompts.pick_user = Box::new(|_| Some("alice".to_owned()));
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+
+        assert!(nav.handle_action(Action::AssignStory { story_id }).is_err());
+
+        db.add_user("alice", User::default()).unwrap();
+        nav.handle_action(Action::AssignStory { story_id }).unwrap();
+        nav.handle_action(Action::UpdateEpicOwner { epic_id })
+            .unwrap();
+        assert_eq!(
+            db.read().unwrap().stories[&story_id].assignee.as_deref(),
+            Some("alice")
+        );
+
+        nav.handle_action(Action::UnassignStory { story_id })
+            .unwrap();
+        nav.handle_action(Action::RemoveEpicOwner { epic_id })
+            .unwrap();
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.stories[&story_id].assignee, None);
+        assert_eq!(db_state.epics[&epic_id].owner, None);
+    }
+
+    #[test]
+    fn handle_action_should_add_remove_and_filter_labels() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut prompts = Prompts::new();
+        prompts.add_label = Box::new(|| Some("backend".to_owned()));
+        prompts.pick_label = Box::new(|labels| labels.first().cloned());
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+
+        assert!(nav
+            .handle_action(Action::RemoveStoryLabel { story_id })
+            .is_err());
+        nav.handle_action(Action::AddEpicLabel { epic_id }).unwrap();
+        nav.handle_action(Action::AddStoryLabel { story_id })
+            .unwrap();
+        nav.handle_action(Action::FilterByLabel).unwrap();
+        nav.handle_action(Action::RemoveEpicLabel { epic_id })
+            .unwrap();
+
+        let db_state = db.read().unwrap();
+        assert!(db_state.epics[&epic_id].labels.is_empty());
+        assert_eq!(
+            db_state.labels(),
+            BTreeSet::from_iter(["backend".to_owned()])
+        );
+    }
+
+    #[test]
+    fn handle_action_should_update_due_dates() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let due_date = NaiveDate::from_ymd_opt(2023, 5, 19);
+
+        let mut prompts = Prompts::new();
+        prompts.update_due_date = Box::new(move || due_date);
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateEpicDueDate { epic_id })
+            .unwrap();
+        nav.handle_action(Action::UpdateStoryDueDate { story_id })
+            .unwrap();
+        nav.handle_action(Action::RemoveStoryDueDate { story_id })
+            .unwrap();
+        nav.handle_action(Action::NavigateToDeadlines).unwrap();
+
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.epics[&epic_id].due_date, due_date);
+        assert_eq!(db_state.stories[&story_id].due_date, None);
+        let current_page = nav.get_current_page().unwrap();
+        assert!(current_page.as_any().downcast_ref::<Deadlines>().is_some());
+    }
+
+    #[test]
+    fn handle_action_should_move_story_and_follow_it() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let from = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), from)
+            .unwrap();
+
+        let mut prompts = Prompts::new();
+        prompts.pick_epic = Box::new(|_, epics| epics.keys().next().copied());
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id: from })
+            .unwrap();
+        nav.handle_action(Action::NavigateToStoryDetail {
+            epic_id: from,
+            story_id,
+        })
+        .unwrap();
+
+        assert!(nav.handle_action(Action::MoveStory { story_id }).is_err());
+        let to = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        nav.handle_action(Action::MoveStory { story_id }).unwrap();
+
+        assert_eq!(db.read().unwrap().epic_of(story_id), Some(to));
+        let page = nav.get_current_page().unwrap();
+        let story_detail = page.as_any().downcast_ref::<StoryDetail>().unwrap();
+        assert_eq!(story_detail.epic_id, to);
+
+        nav.handle_action(Action::Undo).unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+    }
+
+    #[test]
+    fn handle_action_should_rank_stories() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let [a, b, c] = [(); 3].map(|_| {
+            db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap()
+        });
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::MoveStoryToTop { story_id: c })
+            .unwrap();
+        nav.handle_action(Action::MoveStoryDown { story_id: c })
+            .unwrap();
+        nav.handle_action(Action::RankStoryBefore {
+            story_id: b,
+            before_id: a,
+        })
+        .unwrap();
+        nav.handle_action(Action::MoveStoryUp { story_id: c })
+            .unwrap();
+        assert_eq!(db.read().unwrap().epics[&epic_id].stories, vec![b, c, a]);
+
+        nav.handle_action(Action::Undo).unwrap();
+        assert_eq!(db.read().unwrap().epics[&epic_id].stories, vec![b, a, c]);
+    }
+
+    #[test]
+    fn handle_action_should_edit_names_and_descriptions() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("Epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("Story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut prompts = Prompts::new();
+        prompts.update_name = Box::new(|current| Some(format!("{current} 2")));
+        prompts.update_description = Box::new(|current| Some(format!("{current}Text")));
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateEpicName { epic_id })
+            .unwrap();
+        nav.handle_action(Action::UpdateEpicDescription { epic_id })
+            .unwrap();
+        nav.handle_action(Action::UpdateStoryName { story_id })
+            .unwrap();
+        nav.handle_action(Action::UpdateStoryDescription { story_id })
+            .unwrap();
+        assert!(nav
+            .handle_action(Action::UpdateStoryName { story_id: 999 })
+            .is_err());
+
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.epics[&epic_id].name, "Epic 2");
+        assert_eq!(db_state.epics[&epic_id].description, "Text");
+        assert_eq!(db_state.stories[&story_id].name, "Story 2");
+        assert_eq!(db_state.stories[&story_id].description, "Text");
+    }
+
+    #[test]
+    fn handle_action_should_add_edit_and_delete_comments() {
+        let mut db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        db.set_current_user("alice".to_owned());
+        let db = Rc::new(db);
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut prompts = Prompts::new();
+        prompts.write_comment = Box::new(|current| match current {
+            Some(current) => Some(format!("{current}!")),
+            None => Some("Looks good".to_owned()),
         });
+        prompts.pick_comment = Box::new(|comment_ids| comment_ids.last().copied());
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+
+        assert!(nav.handle_action(Action::EditComment { story_id }).is_err());
+        nav.handle_action(Action::CommentOnStory { story_id })
+            .unwrap();
+        nav.handle_action(Action::CommentOnStory { story_id })
+            .unwrap();
+        nav.handle_action(Action::EditComment { story_id }).unwrap();
+        nav.handle_action(Action::DeleteComment { story_id })
+            .unwrap();
+
+        let comments = db.read().unwrap().stories[&story_id].comments.clone();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].body, "Looks good");
+    }
+
+    #[test]
+    fn handle_action_should_delete_story() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -367,7 +1278,7 @@ mod tests {
             .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
             .unwrap();
         let db_state = db.read().unwrap();
-        assert_eq!(db_state.stories.is_empty(), false);
+        assert!(!db_state.stories.is_empty());
         assert!(db_state
             .epics
             .get(&epic_id)
@@ -385,35 +1296,93 @@ mod tests {
             story_id,
         }));
         let current_page = nav.get_current_page().unwrap();
-        assert_eq!(
-            current_page
-                .as_any()
-                .downcast_ref::<StoryDetail>()
-                .is_some(),
-            true
-        );
+        assert!(current_page
+            .as_any()
+            .downcast_ref::<StoryDetail>()
+            .is_some());
 
         nav.handle_action(Action::DeleteStory { epic_id, story_id })
             .unwrap();
 
         let db_state = db.read().unwrap();
         assert!(db_state.stories.is_empty());
-        assert_eq!(
-            db_state
-                .epics
-                .get(&epic_id)
-                .unwrap()
-                .stories
-                .contains(&story_id),
-            false
-        );
+        assert!(!db_state
+            .epics
+            .get(&epic_id)
+            .unwrap()
+            .stories
+            .contains(&story_id));
         let current_page = nav.get_current_page().unwrap();
-        assert_eq!(
-            current_page
-                .as_any()
-                .downcast_ref::<StoryDetail>()
-                .is_some(),
-            false
-        );
+        assert!(current_page
+            .as_any()
+            .downcast_ref::<StoryDetail>()
+            .is_none());
+    }
+
+    #[test]
+    fn handle_action_should_restore_and_purge_from_trash() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.delete_story(epic_id, story_id).unwrap();
+        db.delete_epic(epic_id).unwrap();
+
+        let mut prompts = Prompts::new();
+        prompts.purge_item = Box::new(|| true);
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToTrash).unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        assert!(current_page.as_any().downcast_ref::<TrashBin>().is_some());
+
+        nav.handle_action(Action::RestoreEpic { epic_id }).unwrap();
+        nav.handle_action(Action::PurgeStory { story_id }).unwrap();
+
+        let db_state = db.read().unwrap();
+        assert!(db_state.epics.contains_key(&epic_id));
+        assert!(db_state.stories.is_empty());
+        assert!(db_state.trash.is_empty());
+    }
+
+    #[test]
+    fn handle_action_should_undo_and_redo() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut prompts = Prompts::new();
+        prompts.create_story = Box::new(|| Story::new("name".to_owned(), "".to_owned()));
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.set_prompts(prompts);
+        nav.add_page(Box::new(EpicDetail::new(Rc::clone(&db), epic_id)));
+
+        nav.handle_action(Action::CreateStory { epic_id }).unwrap();
+        let story_id = *db.read().unwrap().stories.keys().next().unwrap();
+        nav.handle_action(Action::NavigateToStoryDetail { epic_id, story_id })
+            .unwrap();
+        assert_eq!(nav.get_page_count(), 3);
+
+        nav.handle_action(Action::Undo).unwrap();
+        let db_state = db.read().unwrap();
+        assert!(db_state.stories.is_empty());
+        assert!(db_state.epics[&epic_id].stories.is_empty());
+        assert_eq!(nav.get_page_count(), 2);
+        assert!(nav
+            .get_current_page()
+            .unwrap()
+            .as_any()
+            .downcast_ref::<EpicDetail>()
+            .is_some());
+
+        nav.handle_action(Action::Redo).unwrap();
+        let db_state = db.read().unwrap();
+        assert_eq!(db_state.stories[&story_id].name, "name");
+        assert!(db_state.epics[&epic_id].stories.contains(&story_id));
     }
 }
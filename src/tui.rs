@@ -0,0 +1,318 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{self, ClearType},
+};
+
+use crate::io_utils;
+use crate::models::Action;
+use crate::navigator::Navigator;
+use crate::ui::{Board, Page};
+
+/// What a key press does on the current page.
+#[derive(Debug, PartialEq, Eq)]
+enum KeyCommand {
+    /// Moves the selection up or down the page's list.
+    Select(isize),
+    /// Opens the selected row.
+    Open,
+    /// Goes back to the previous page.
+    Back,
+    /// Moves the selected story to the previous or next column of the board, elsewhere
+    /// left goes back.
+    Column(isize),
+    /// Reads the rest of a line starting with this text, for ids, searches and ranking.
+    Line(String),
+    /// A single-key command of the page.
+    Input(char),
+    Exit,
+    Ignore,
+}
+
+fn key_command(key: KeyEvent) -> KeyCommand {
+    if key.kind == KeyEventKind::Release {
+        return KeyCommand::Ignore;
+    }
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => KeyCommand::Exit,
+        KeyCode::Up | KeyCode::Char('k') => KeyCommand::Select(-1),
+        KeyCode::Down | KeyCode::Char('j') => KeyCommand::Select(1),
+        KeyCode::PageUp => KeyCommand::Select(-10),
+        KeyCode::PageDown => KeyCommand::Select(10),
+        KeyCode::Enter => KeyCommand::Open,
+        KeyCode::Esc | KeyCode::Backspace => KeyCommand::Back,
+        KeyCode::Left => KeyCommand::Column(-1),
+        KeyCode::Right => KeyCommand::Column(1),
+        KeyCode::Char(':') => KeyCommand::Line(String::new()),
+        KeyCode::Char(c) if c == '/' || c.is_ascii_digit() => KeyCommand::Line(c.to_string()),
+        KeyCode::Char(c) => KeyCommand::Input(c),
+        _ => KeyCommand::Ignore,
+    }
+}
+
+/// Leaves the alternate screen again, also when the board exits with an error.
+struct AlternateScreen;
+
+impl AlternateScreen {
+    fn enter() -> Result<Self> {
+        execute!(io::stdout(), terminal::EnterAlternateScreen)?;
+        Ok(AlternateScreen)
+    }
+}
+
+impl Drop for AlternateScreen {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(io::stdout(), terminal::LeaveAlternateScreen);
+    }
+}
+
+/// Runs the board full-screen until it is quit. Pages draw and prompt as in the line mode,
+/// the terminal is only in raw mode while waiting for a key.
+pub fn run(navigator: &mut Navigator) -> Result<()> {
+    let _screen = AlternateScreen::enter()?;
+    navigator.set_render(|page| render(page).map(drop));
+
+    loop {
+        let Some(page) = navigator.get_current_page() else {
+            return Ok(());
+        };
+
+        let rows = match render(page) {
+            Ok(rows) => rows,
+            Err(err) => {
+                show_error(&format!("Error rendering page: {err:#}"))?;
+                return Ok(());
+            }
+        };
+
+        let command = match key_command(read_key()?) {
+            KeyCommand::Line(start) => KeyCommand::Line(read_line(&start)?),
+            command => command,
+        };
+        match key_action(page, &rows, command) {
+            Ok(Some(action)) => {
+                if let Err(err) = navigator.handle_action(action) {
+                    show_error(&format!("{err:#}"))?;
+                }
+            }
+            Ok(None) => {}
+            Err(err) => {
+                show_error(&format!("Error handling page input: {err:#}"))?;
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Draws the page on the cleared screen, returning the rows that can be selected on it.
+fn render(page: &dyn Page) -> Result<Vec<Action>> {
+    let rows = selectable_rows(page)?;
+    clear()?;
+    page.draw_page()?;
+    if !rows.is_empty() {
+        println!("[up/down or j/k] select | [enter] open | [esc] back | [:] type a command");
+    }
+    Ok(rows)
+}
+
+/// The rows of the page, with its selection kept within them as the list may have shrunk.
+fn selectable_rows(page: &dyn Page) -> Result<Vec<Action>> {
+    let rows = page.rows()?;
+    if let Some(selection) = page.row_selection() {
+        selection.step(0, rows.len());
+    }
+    Ok(rows)
+}
+
+/// The action of `command` on the page drawn with `rows`. A `Line` holds the whole line
+/// by then.
+fn key_action(page: &dyn Page, rows: &[Action], command: KeyCommand) -> Result<Option<Action>> {
+    match command {
+        KeyCommand::Select(offset) => {
+            if let Some(selection) = page.row_selection() {
+                selection.step(offset, rows.len());
+            }
+            Ok(None)
+        }
+        KeyCommand::Open => {
+            let selected = page.row_selection().and_then(|selection| selection.get());
+            Ok(selected.and_then(|row| rows.get(row).cloned()))
+        }
+        KeyCommand::Back => page.handle_input("p"),
+        KeyCommand::Column(offset) if page.as_any().is::<Board>() => match offset < 0 {
+            true => page.handle_input("<"),
+            false => page.handle_input(">"),
+        },
+        KeyCommand::Column(offset) if offset < 0 => page.handle_input("p"),
+        KeyCommand::Column(_) => Ok(None),
+        KeyCommand::Line(line) => page.handle_input(line.trim()),
+        KeyCommand::Input(c) => page.handle_input(&c.to_string()),
+        KeyCommand::Exit => Ok(Some(Action::Exit)),
+        KeyCommand::Ignore => Ok(None),
+    }
+}
+
+fn clear() -> Result<()> {
+    execute!(
+        io::stdout(),
+        terminal::Clear(ClearType::All),
+        terminal::Clear(ClearType::Purge),
+        cursor::MoveTo(0, 0)
+    )?;
+    Ok(())
+}
+
+fn read_key() -> Result<KeyEvent> {
+    terminal::enable_raw_mode()?;
+    let key = loop {
+        match event::read() {
+            Ok(Event::Key(key)) => break Ok(key),
+            Ok(_) => continue,
+            Err(err) => break Err(err),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    Ok(key?)
+}
+
+/// Echoes `start`, which was typed as a key, and reads the rest of the line.
+fn read_line(start: &str) -> Result<String> {
+    print!("\n> {start}");
+    io::stdout().flush()?;
+    Ok(format!("{start}{}", io_utils::get_user_input()))
+}
+
+fn show_error(message: &str) -> Result<()> {
+    println!("{message}\nPress any key to continue...");
+    read_key()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{test_utils::MockDB, JiraDatabase},
+        models::{Epic, Status, Story},
+    };
+    use std::rc::Rc;
+
+    fn press(code: KeyCode) -> KeyCommand {
+        key_command(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn key_command_should_map_navigation_keys() {
+        assert_eq!(press(KeyCode::Down), KeyCommand::Select(1));
+        assert_eq!(press(KeyCode::Char('j')), KeyCommand::Select(1));
+        assert_eq!(press(KeyCode::Up), KeyCommand::Select(-1));
+        assert_eq!(press(KeyCode::Char('k')), KeyCommand::Select(-1));
+        assert_eq!(press(KeyCode::Enter), KeyCommand::Open);
+        assert_eq!(press(KeyCode::Esc), KeyCommand::Back);
+        assert_eq!(press(KeyCode::Left), KeyCommand::Column(-1));
+        assert_eq!(press(KeyCode::Right), KeyCommand::Column(1));
+        assert_eq!(
+            key_command(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            KeyCommand::Exit
+        );
+    }
+
+    #[test]
+    fn key_command_should_start_lines_for_ids_and_searches() {
+        assert_eq!(press(KeyCode::Char('c')), KeyCommand::Input('c'));
+        assert_eq!(press(KeyCode::Char('!')), KeyCommand::Input('!'));
+        assert_eq!(press(KeyCode::Char('4')), KeyCommand::Line("4".to_owned()));
+        assert_eq!(press(KeyCode::Char('/')), KeyCommand::Line("/".to_owned()));
+        assert_eq!(press(KeyCode::Char(':')), KeyCommand::Line(String::new()));
+        assert_eq!(press(KeyCode::F(1)), KeyCommand::Ignore);
+    }
+
+    #[test]
+    fn key_action_should_act_on_the_current_page() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+        let page = nav.get_current_page().unwrap();
+
+        // Nothing to open in an epic without stories, and left goes back off the board.
+        let rows = selectable_rows(page).unwrap();
+        assert_eq!(key_action(page, &rows, KeyCommand::Open).unwrap(), None);
+        assert_eq!(
+            key_action(page, &rows, KeyCommand::Column(-1)).unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(
+            key_action(page, &rows, KeyCommand::Column(1)).unwrap(),
+            None
+        );
+        assert_eq!(
+            key_action(page, &rows, KeyCommand::Line(" b ".to_owned())).unwrap(),
+            Some(Action::NavigateToBoard {
+                epic_id: Some(epic_id)
+            })
+        );
+
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        nav.handle_action(Action::NavigateToBoard { epic_id: None })
+            .unwrap();
+        let page = nav.get_current_page().unwrap();
+        let rows = selectable_rows(page).unwrap();
+        assert_eq!(
+            key_action(page, &rows, KeyCommand::Select(1)).unwrap(),
+            None
+        );
+        assert_eq!(
+            key_action(page, &rows, KeyCommand::Column(1)).unwrap(),
+            Some(Action::UpdateStoryStatus {
+                story_id,
+                to: Some(Status::InProgress)
+            })
+        );
+        assert_eq!(
+            key_action(page, &rows, KeyCommand::Open).unwrap(),
+            Some(Action::NavigateToStoryDetail { epic_id, story_id })
+        );
+    }
+
+    #[test]
+    fn selectable_rows_should_keep_the_selection_within_the_list() {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+        let [first, second] = [(); 2].map(|_| {
+            db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap()
+        });
+        let nav = Navigator::new(Rc::clone(&db));
+        let page = nav.get_current_page().unwrap();
+
+        let rows = selectable_rows(page).unwrap();
+        key_action(page, &rows, KeyCommand::Select(5)).unwrap();
+        assert_eq!(
+            key_action(page, &rows, KeyCommand::Open).unwrap(),
+            Some(Action::NavigateToEpicDetail { epic_id: second })
+        );
+
+        db.delete_epic(second).unwrap();
+        let rows = selectable_rows(page).unwrap();
+        assert_eq!(page.row_selection().unwrap().get(), Some(0));
+        assert_eq!(
+            key_action(page, &rows, KeyCommand::Open).unwrap(),
+            Some(Action::NavigateToEpicDetail { epic_id: first })
+        );
+
+        db.delete_epic(first).unwrap();
+        let rows = selectable_rows(page).unwrap();
+        assert_eq!(page.row_selection().unwrap().get(), None);
+        assert_eq!(key_action(page, &rows, KeyCommand::Open).unwrap(), None);
+    }
+}
@@ -0,0 +1,365 @@
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::{cell::Cell, rc::Rc};
+
+use crate::db::JiraDatabase;
+use crate::models::{Action, DBState, Status};
+
+use super::{
+    page_helpers::RowSelection,
+    table::{Column, Table},
+    Page,
+};
+
+const MIN_COLUMN_WIDTH: usize = 12;
+
+/// The stories of one epic, or of all of them, in a column per status. Within a column
+/// stories are in epic and then rank order.
+pub struct Board {
+    pub epic_id: Option<u32>,
+    pub db: Rc<JiraDatabase>,
+    selection: RowSelection,
+    /// The row selected when the board was last shown, and the story on it.
+    shown_selection: Cell<Option<(usize, u32)>>,
+}
+
+impl Board {
+    pub fn new(db: Rc<JiraDatabase>, epic_id: Option<u32>) -> Self {
+        Self {
+            epic_id,
+            db,
+            selection: RowSelection::default(),
+            shown_selection: Cell::new(None),
+        }
+    }
+
+    /// The story ids of each column, in the order of `Status::ALL`.
+    fn columns(&self, db_state: &DBState) -> Result<Vec<Vec<(u32, u32)>>> {
+        let epics = match self.epic_id {
+            Some(epic_id) => {
+                let epic = db_state
+                    .epics
+                    .get_key_value(&epic_id)
+                    .ok_or_else(|| anyhow!("Epic with {epic_id} not found"))?;
+                vec![epic]
+            }
+            None => db_state
+                .epics
+                .iter()
+                .sorted_by_key(|(epic_id, _)| **epic_id)
+                .collect(),
+        };
+
+        let mut columns = vec![Vec::new(); Status::ALL.len()];
+        for (epic_id, epic) in epics {
+            for story_id in &epic.stories {
+                let Some(story) = db_state.stories.get(story_id) else {
+                    continue;
+                };
+                let column = Status::ALL
+                    .iter()
+                    .position(|status| *status == story.status);
+                if let Some(column) = column {
+                    columns[column].push((*story_id, *epic_id));
+                }
+            }
+        }
+        Ok(columns)
+    }
+
+    /// The stories with the epic they belong to, column by column. Rows are selected in
+    /// this order.
+    fn cards(&self, db_state: &DBState) -> Result<Vec<(u32, u32)>> {
+        Ok(self.columns(db_state)?.into_iter().flatten().collect())
+    }
+
+    /// Keeps the selection on the story it was on when the board was last shown, wherever
+    /// a move, an undo or a teammate put it since. Once another row is picked, the
+    /// selection stays there and follows the story on it instead.
+    fn follow_selected_story(&self, cards: &[(u32, u32)]) {
+        if let (Some(row), Some((shown_row, story_id))) =
+            (self.selection.get(), self.shown_selection.get())
+        {
+            if row == shown_row {
+                if let Some(row) = cards.iter().position(|(id, _)| *id == story_id) {
+                    self.selection.set(Some(row));
+                }
+            }
+        }
+        let shown = self
+            .selection
+            .get()
+            .and_then(|row| Some((row, cards.get(row)?.0)));
+        self.shown_selection.set(shown);
+    }
+
+    /// Moves `story_id` one column to the left (`-1`) or right (`1`).
+    fn move_story(&self, db_state: &DBState, story_id: u32, offset: isize) -> Option<Action> {
+        let story = db_state.stories.get(&story_id)?;
+        let column = Status::ALL
+            .iter()
+            .position(|status| *status == story.status)?;
+        let status = Status::ALL.get(column.checked_add_signed(offset)?)?.clone();
+
+        Some(Action::UpdateStoryStatus {
+            story_id,
+            to: Some(status),
+        })
+    }
+
+    /// `<` and `>` move the selected story, `< :id:` and `> :id:` any story on the board.
+    fn move_command(&self, input: &str) -> Result<Option<Action>> {
+        let (offset, story) = match input.split_at(1) {
+            ("<", story) => (-1, story.trim()),
+            (">", story) => (1, story.trim()),
+            _ => return Ok(None),
+        };
+
+        let db_state = self.db.snapshot()?;
+        let cards = self.cards(&db_state)?;
+        let story_id = match story {
+            "" => self
+                .selection
+                .get()
+                .and_then(|row| cards.get(row))
+                .map(|(story_id, _)| *story_id),
+            story => self
+                .db
+                .key_prefix()
+                .parse(story)
+                .filter(|story_id| cards.iter().any(|(id, _)| id == story_id)),
+        };
+        Ok(story_id.and_then(|story_id| self.move_story(&db_state, story_id, offset)))
+    }
+}
+
+impl Page for Board {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read()?;
+        let keys = self.db.key_prefix();
+        let columns = self.columns(&db_state)?;
+        self.follow_selected_story(&columns.concat());
+
+        match self.epic_id {
+            Some(epic_id) => println!(
+                "---------------------------- BOARD: {} ----------------------------",
+                db_state.epics[&epic_id].name
+            ),
+            None => println!("---------------------------- BOARD ----------------------------"),
+        }
+        let table = Table::new(
+            Status::ALL
+                .iter()
+                .map(|status| Column::wide(status.to_string().to_uppercase(), MIN_COLUMN_WIDTH)),
+        );
+        println!("{}", table.header());
+        println!("{}", table.rule());
+
+        let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+        for row in 0..rows {
+            let cards = columns
+                .iter()
+                .map(|column| match column.get(row) {
+                    Some((story_id, _)) => {
+                        format!(
+                            "{} {}",
+                            keys.key(*story_id),
+                            db_state.stories[story_id].name
+                        )
+                    }
+                    None => String::new(),
+                })
+                .collect::<Vec<_>>();
+            let cards = cards.iter().map(String::as_str).collect::<Vec<_>>();
+
+            let mut first_card = 0;
+            let cells = table
+                .cells(&cards)
+                .into_iter()
+                .zip(&columns)
+                .map(|(cell, column)| {
+                    let index = first_card + row;
+                    first_card += column.len();
+                    match row < column.len() {
+                        true => self.selection.highlight(index, cell),
+                        false => cell,
+                    }
+                })
+                .collect();
+            println!("{}", table.join(cells));
+        }
+
+        println!();
+        println!();
+
+        println!("[p] previous | [<] [>] move the selected story | [< :id:] [> :id:] move a story to the previous or next column | [:id:] navigate to story | [z] undo | [Z] redo");
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "z" => Ok(Some(Action::Undo)),
+            "Z" => Ok(Some(Action::Redo)),
+            input if input.starts_with(['<', '>']) => self.move_command(input),
+            input => {
+                let Some(story_id) = self.db.key_prefix().parse(input) else {
+                    return Ok(None);
+                };
+
+                let db_state = self.db.snapshot()?;
+                let card = self
+                    .cards(&db_state)?
+                    .into_iter()
+                    .find(|(id, _)| *id == story_id);
+                Ok(card
+                    .map(|(story_id, epic_id)| Action::NavigateToStoryDetail { epic_id, story_id }))
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn rows(&self) -> Result<Vec<Action>> {
+        let cards = self.cards(&self.db.read()?)?;
+        self.follow_selected_story(&cards);
+        Ok(cards
+            .into_iter()
+            .map(|(story_id, epic_id)| Action::NavigateToStoryDetail { epic_id, story_id })
+            .collect())
+    }
+
+    fn row_selection(&self) -> Option<&RowSelection> {
+        Some(&self.selection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_utils::MockDB,
+        models::{Epic, Story},
+    };
+
+    fn build_page(epic_id: Option<u32>) -> Board {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+        Board::new(db, epic_id)
+    }
+
+    #[test]
+    fn columns_should_group_stories_by_status() {
+        let page = build_page(None);
+        let create_story = |epic_id: u32, status: Status| {
+            let story_id = page
+                .db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            page.db.update_story_status(story_id, status).unwrap();
+            story_id
+        };
+        let epic_id = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let open = create_story(epic_id, Status::Open);
+        let closed = create_story(epic_id, Status::Closed);
+        let other_epic_id = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let other = create_story(other_epic_id, Status::Open);
+
+        let db_state = page.db.read().unwrap();
+        assert_eq!(
+            page.columns(&db_state).unwrap(),
+            vec![
+                vec![(open, epic_id), (other, other_epic_id)],
+                vec![],
+                vec![],
+                vec![(closed, epic_id)]
+            ]
+        );
+        assert!(page.draw_page().is_ok());
+
+        let epic_board = Board::new(Rc::clone(&page.db), Some(epic_id));
+        assert_eq!(
+            epic_board.cards(&db_state).unwrap(),
+            vec![(open, epic_id), (closed, epic_id)]
+        );
+        assert!(epic_board.draw_page().is_ok());
+        assert!(Board::new(Rc::clone(&page.db), Some(999))
+            .draw_page()
+            .is_err());
+    }
+
+    #[test]
+    fn handle_input_should_move_stories_between_columns() {
+        let page = build_page(None);
+        let epic_id = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let [first, second] = [(); 2].map(|_| {
+            page.db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap()
+        });
+
+        assert_eq!(page.handle_input("<").unwrap(), None);
+        assert_eq!(page.handle_input(&format!("< {first}")).unwrap(), None);
+        assert_eq!(
+            page.handle_input(&format!("> {second}")).unwrap(),
+            Some(Action::UpdateStoryStatus {
+                story_id: second,
+                to: Some(Status::InProgress)
+            })
+        );
+
+        page.selection.step(0, 2);
+        page.rows().unwrap();
+        assert_eq!(
+            page.handle_input(">").unwrap(),
+            Some(Action::UpdateStoryStatus {
+                story_id: first,
+                to: Some(Status::InProgress)
+            })
+        );
+        // Until the move lands, as it wouldn't on a conflict, the selection stays put.
+        page.rows().unwrap();
+        assert_eq!(page.selection.get(), Some(0));
+
+        // The moved story stays selected, now last as the only one in progress, and is
+        // followed back when the move is undone.
+        page.db
+            .update_story_status(first, Status::InProgress)
+            .unwrap();
+        page.rows().unwrap();
+        assert_eq!(page.selection.get(), Some(1));
+        page.db.update_story_status(first, Status::Open).unwrap();
+        page.rows().unwrap();
+        assert_eq!(page.selection.get(), Some(0));
+
+        // A picked row is kept, with whichever story is on it.
+        page.selection.step(1, 2);
+        page.rows().unwrap();
+        assert_eq!(page.selection.get(), Some(1));
+
+        assert_eq!(
+            page.handle_input(&first.to_string()).unwrap(),
+            Some(Action::NavigateToStoryDetail {
+                epic_id,
+                story_id: first
+            })
+        );
+        assert_eq!(page.handle_input("999").unwrap(), None);
+        assert_eq!(
+            page.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::Result;
+use chrono::{Days, NaiveDate};
+use itertools::Itertools;
+use std::{cell::Cell, rc::Rc};
+
+use crate::db::JiraDatabase;
+use crate::io_utils;
+use crate::models::{Action, DBState, Status};
+
+use super::{
+    page_helpers::due_date_column,
+    table::{Column, Table},
+    Page,
+};
+
+const DEFAULT_DAYS: u64 = 14;
+/// How much `[+]` and `[-]` widen or narrow the window.
+const DAYS_STEP: u64 = 7;
+
+/// Epics and stories due in the next days across the board, soonest first, after the
+/// overdue ones. Resolved and closed items are left out.
+pub struct Deadlines {
+    pub db: Rc<JiraDatabase>,
+    days: Cell<u64>,
+}
+
+/// A listed item, stories with the epic they belong to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum DueItem {
+    Epic(u32),
+    Story { epic_id: u32, story_id: u32 },
+}
+
+impl Deadlines {
+    pub fn new(db: Rc<JiraDatabase>) -> Self {
+        Self {
+            db,
+            days: Cell::new(DEFAULT_DAYS),
+        }
+    }
+
+    fn due_items(&self, db_state: &DBState, today: NaiveDate) -> Vec<(NaiveDate, DueItem)> {
+        let Some(until) = today.checked_add_days(Days::new(self.days.get())) else {
+            return Vec::new();
+        };
+        let is_due = |due_date: Option<NaiveDate>, status: &Status| {
+            due_date.filter(|due_date| *due_date <= until && !status.is_done())
+        };
+
+        let epics = db_state.epics.iter().filter_map(|(epic_id, epic)| {
+            let due_date = is_due(epic.due_date, &epic.status)?;
+            Some((due_date, DueItem::Epic(*epic_id)))
+        });
+        let stories = db_state.epics.iter().flat_map(|(epic_id, epic)| {
+            epic.stories.iter().filter_map(|story_id| {
+                let story = db_state.stories.get(story_id)?;
+                let due_date = is_due(story.due_date, &story.status)?;
+                let item = DueItem::Story {
+                    epic_id: *epic_id,
+                    story_id: *story_id,
+                };
+                Some((due_date, item))
+            })
+        });
+
+        epics
+            .chain(stories)
+            .sorted_by_key(|(due_date, item)| (*due_date, item.id()))
+            .collect()
+    }
+}
+
+impl DueItem {
+    fn id(self) -> u32 {
+        match self {
+            DueItem::Epic(epic_id) => epic_id,
+            DueItem::Story { story_id, .. } => story_id,
+        }
+    }
+}
+
+impl Page for Deadlines {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read()?;
+        let keys = self.db.key_prefix();
+        let today = io_utils::today();
+
+        println!("--------------------------- DEADLINES ---------------------------");
+        println!(
+            "Overdue and due in the next {} days, overdue items are marked with !",
+            self.days.get()
+        );
+        println!();
+        let table = Table::new([
+            Column::fixed("id", 11),
+            Column::wide("name", 24),
+            Column::fixed("type", 5),
+            Column::fixed("due", 12),
+            Column::fixed("status", 13),
+        ]);
+        println!("{}", table.header());
+        for (_, item) in self.due_items(&db_state, today) {
+            let (kind, name, due_date, status) = match item {
+                DueItem::Epic(epic_id) => {
+                    let epic = &db_state.epics[&epic_id];
+                    ("epic", &epic.name, epic.due_date, &epic.status)
+                }
+                DueItem::Story { story_id, .. } => {
+                    let story = &db_state.stories[&story_id];
+                    ("story", &story.name, story.due_date, &story.status)
+                }
+            };
+            let row = table.row(&[
+                &keys.key(item.id()),
+                name,
+                kind,
+                &due_date_column(due_date, status, today),
+                &status.to_string(),
+            ]);
+            println!("{row}");
+        }
+
+        println!();
+        println!();
+
+        println!("[p] previous | [+] {DAYS_STEP} more days | [-] {DAYS_STEP} fewer days | [:id:] navigate to epic or story | [z] undo | [Z] redo");
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "+" => {
+                self.days.set(self.days.get() + DAYS_STEP);
+                Ok(None)
+            }
+            "-" => {
+                self.days
+                    .set(self.days.get().saturating_sub(DAYS_STEP).max(DAYS_STEP));
+                Ok(None)
+            }
+            "z" => Ok(Some(Action::Undo)),
+            "Z" => Ok(Some(Action::Redo)),
+            input => {
+                let Some(id) = self.db.key_prefix().parse(input) else {
+                    return Ok(None);
+                };
+
+                let db_state = self.db.snapshot()?;
+                let item = self
+                    .due_items(&db_state, io_utils::today())
+                    .into_iter()
+                    .map(|(_, item)| item)
+                    .find(|item| item.id() == id);
+                Ok(item.map(|item| match item {
+                    DueItem::Epic(epic_id) => Action::NavigateToEpicDetail { epic_id },
+                    DueItem::Story { epic_id, story_id } => {
+                        Action::NavigateToStoryDetail { epic_id, story_id }
+                    }
+                }))
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_utils::MockDB,
+        models::{Epic, Story},
+    };
+
+    fn build_page() -> Deadlines {
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
+
+        Deadlines::new(db)
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 5, day).unwrap()
+    }
+
+    #[test]
+    fn due_items_should_list_open_items_due_soon_by_date() {
+        let page = build_page();
+        let epic_id = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        page.db
+            .update_epic_due_date(epic_id, Some(date(20)))
+            .unwrap();
+        let create_story = |due_date: NaiveDate| {
+            let story_id = page
+                .db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            page.db
+                .update_story_due_date(story_id, Some(due_date))
+                .unwrap();
+            story_id
+        };
+        let overdue = create_story(date(10));
+        let soon = create_story(date(18));
+        create_story(date(31));
+        let resolved = create_story(date(12));
+        page.db
+            .update_story_status(resolved, Status::Resolved)
+            .unwrap();
+
+        let db_state = page.db.read().unwrap();
+        let items = page
+            .due_items(&db_state, date(15))
+            .into_iter()
+            .map(|(_, item)| item)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                DueItem::Story {
+                    epic_id,
+                    story_id: overdue
+                },
+                DueItem::Story {
+                    epic_id,
+                    story_id: soon
+                },
+                DueItem::Epic(epic_id),
+            ]
+        );
+
+        page.handle_input("+").unwrap();
+        assert_eq!(page.due_items(&db_state, date(15)).len(), 4);
+        page.handle_input("-").unwrap();
+        page.handle_input("-").unwrap();
+        assert_eq!(page.days.get(), DAYS_STEP);
+        assert!(page.draw_page().is_ok());
+    }
+
+    #[test]
+    fn handle_input_should_navigate_to_listed_items() {
+        let page = build_page();
+        let epic_id = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = page
+            .db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        page.db
+            .update_story_due_date(story_id, Some(io_utils::today()))
+            .unwrap();
+
+        assert_eq!(
+            page.handle_input(&story_id.to_string()).unwrap(),
+            Some(Action::NavigateToStoryDetail { epic_id, story_id })
+        );
+        assert_eq!(page.handle_input(&epic_id.to_string()).unwrap(), None);
+        assert_eq!(
+            page.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+    }
+}
@@ -1,14 +1,167 @@
 use anyhow::{anyhow, Result};
-use itertools::Itertools;
-use std::rc::Rc;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
-use super::{page_helpers::get_column_string, Page};
+use super::{
+    page_helpers::{due_date_column, labels_column, print_description, LabelFilter, RowSelection},
+    table::{Column, Table},
+    Page,
+};
 use crate::db::JiraDatabase;
-use crate::models::Action;
+use crate::io_utils;
+use crate::models::{Action, DBState, Epic, Status, Story};
 
 pub struct EpicDetail {
     pub epic_id: u32,
     pub db: Rc<JiraDatabase>,
+    sort: Cell<StorySort>,
+    label_filter: LabelFilter,
+    status_filter: RefCell<Option<Status>>,
+    /// Narrows the stories to those with this text in their name or description.
+    search: RefCell<Option<String>>,
+    selection: RowSelection,
+}
+
+/// Order of the stories table, cycled with `[s]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+enum StorySort {
+    /// The epic's own order.
+    #[default]
+    Manual,
+    Id,
+    Name,
+    Status,
+    Priority,
+}
+
+impl StorySort {
+    fn next(self) -> Self {
+        match self {
+            StorySort::Manual => StorySort::Id,
+            StorySort::Id => StorySort::Name,
+            StorySort::Name => StorySort::Status,
+            StorySort::Status => StorySort::Priority,
+            StorySort::Priority => StorySort::Manual,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            StorySort::Manual => "manual order",
+            StorySort::Id => "id",
+            StorySort::Name => "name",
+            StorySort::Status => "status",
+            StorySort::Priority => "priority",
+        }
+    }
+}
+
+impl EpicDetail {
+    pub fn new(db: Rc<JiraDatabase>, epic_id: u32) -> Self {
+        Self {
+            epic_id,
+            db,
+            sort: Cell::default(),
+            label_filter: LabelFilter::default(),
+            status_filter: RefCell::default(),
+            search: RefCell::default(),
+            selection: RowSelection::default(),
+        }
+    }
+
+    /// Only lists the stories carrying `label`, all of them for `None`.
+    pub fn filter_by_label(&self, label: Option<String>) {
+        self.label_filter.set(label);
+    }
+
+    /// The listed stories of `epic`, in table order. Equal stories keep the epic's order.
+    fn story_ids(&self, db_state: &DBState, epic: &Epic) -> Vec<u32> {
+        let search = self
+            .search
+            .borrow()
+            .as_ref()
+            .map(|text| text.to_lowercase());
+        let status_filter = self.status_filter.borrow();
+        let mut stories = epic
+            .stories
+            .iter()
+            .filter_map(|story_id| Some((*story_id, db_state.stories.get(story_id)?)))
+            .filter(|(_, story)| self.label_filter.matches(&story.labels))
+            .filter(|(_, story)| {
+                status_filter
+                    .as_ref()
+                    .is_none_or(|status| story.status == *status)
+            })
+            .filter(|(_, story)| search.as_ref().is_none_or(|text| mentions(story, text)))
+            .collect::<Vec<_>>();
+
+        match self.sort.get() {
+            StorySort::Manual => {}
+            StorySort::Id => stories.sort_by_key(|(story_id, _)| *story_id),
+            StorySort::Name => stories.sort_by_cached_key(|(_, story)| story.name.to_lowercase()),
+            StorySort::Status => stories.sort_by_key(|(_, story)| story.status.clone()),
+            StorySort::Priority => stories.sort_by_key(|(_, story)| story.priority),
+        }
+        stories.into_iter().map(|(story_id, _)| story_id).collect()
+    }
+
+    /// Shows the next status only, and all of them again after the last one.
+    fn cycle_status_filter(&self) {
+        let mut status_filter = self.status_filter.borrow_mut();
+        *status_filter = match &*status_filter {
+            None => Some(Status::ALL[0].clone()),
+            Some(status) => Status::ALL
+                .iter()
+                .skip_while(|other| *other != status)
+                .nth(1)
+                .cloned(),
+        };
+    }
+
+    /// `up :id:`, `down :id:`, `top :id:` or `rank :id: before :id:`, for stories of the epic.
+    fn rank_command(&self, db_state: &DBState, input: &str) -> Option<Action> {
+        let epic = db_state.epics.get(&self.epic_id)?;
+        let keys = self.db.key_prefix();
+        let own_story = |input: &str| keys.parse(input).filter(|id| epic.stories.contains(id));
+
+        let words = input.split_whitespace().collect::<Vec<_>>();
+        let action = match words.as_slice() {
+            ["up", story] => Action::MoveStoryUp {
+                story_id: own_story(story)?,
+            },
+            ["down", story] => Action::MoveStoryDown {
+                story_id: own_story(story)?,
+            },
+            ["top", story] => Action::MoveStoryToTop {
+                story_id: own_story(story)?,
+            },
+            ["rank", story, "before", before] => Action::RankStoryBefore {
+                story_id: own_story(story)?,
+                before_id: own_story(before)?,
+            },
+            _ => return None,
+        };
+        Some(action)
+    }
+
+    fn draw_filters(&self) {
+        self.label_filter.draw();
+        if let Some(status) = &*self.status_filter.borrow() {
+            println!("Only showing stories that are {status}");
+            println!();
+        }
+        if let Some(text) = &*self.search.borrow() {
+            println!("Only showing stories mentioning \"{text}\"");
+            println!();
+        }
+    }
+}
+
+/// `text` is lowercase.
+fn mentions(story: &Story, text: &str) -> bool {
+    story.name.to_lowercase().contains(text) || story.description.to_lowercase().contains(text)
 }
 
 impl Page for EpicDetail {
@@ -19,49 +172,128 @@ impl Page for EpicDetail {
             .get(&self.epic_id)
             .ok_or_else(|| anyhow!(format!("Epic with {} not found", &self.epic_id)))?;
 
+        let keys = self.db.key_prefix();
+
+        let sort = self.sort.get();
+        let today = io_utils::today();
+
         println!("------------------------------ EPIC ------------------------------");
-        println!("  id  |     name     |         description         |    status    ");
-        let epic_id = get_column_string(&self.epic_id.to_string(), 5);
-        let epic_name = get_column_string(&epic.name, 12);
-        let epic_description = get_column_string(&epic.description, 27);
-        let epic_status = get_column_string(&epic.status.to_string(), 13);
-        println!("{epic_id} | {epic_name} | {epic_description} | {epic_status}");
+        let table = Table::new([
+            Column::fixed("id", 9),
+            Column::wide("name", 26),
+            Column::fixed("owner", 10),
+            Column::fixed("priority", 9),
+            Column::fixed("status", 13),
+        ]);
+        println!("{}", table.header());
+        println!(
+            "{}",
+            table.row(&[
+                &keys.key(self.epic_id),
+                &epic.name,
+                epic.owner.as_deref().unwrap_or(""),
+                &epic.priority.to_string(),
+                &epic.status.to_string(),
+            ])
+        );
+        if !epic.labels.is_empty() {
+            println!("labels: {}", labels_column(&epic.labels));
+        }
+        if epic.due_date.is_some() {
+            println!(
+                "due: {}",
+                due_date_column(epic.due_date, &epic.status, today)
+            );
+        }
+        print_description(&epic.description);
 
         println!();
 
         println!("---------------------------- STORIES ----------------------------");
-        println!("     id     |               name               |      status      ");
-        db_state.stories.keys().sorted().for_each(|id| {
-            let story = &db_state.stories[id];
-            let story_id = get_column_string(&id.to_string(), 11);
-            let story_name = get_column_string(&story.name, 32);
-            let story_status = get_column_string(&story.status.to_string(), 17);
-            println!("{story_id} | {story_name} | {story_status}");
-        });
+        self.draw_filters();
+        let table = Table::new([
+            Column::fixed("id", 11),
+            Column::wide("name", 18),
+            Column::fixed("assignee", 10),
+            Column::wide("labels", 14).optional(),
+            Column::fixed("due", 12).optional(),
+            Column::fixed("priority", 9),
+            Column::fixed("status", 13),
+        ]);
+        println!("{}", table.header());
+        for (index, story_id) in self.story_ids(&db_state, epic).into_iter().enumerate() {
+            let story = &db_state.stories[&story_id];
+            let row = table.row(&[
+                &keys.key(story_id),
+                &story.name,
+                story.assignee.as_deref().unwrap_or(""),
+                &labels_column(&story.labels),
+                &due_date_column(story.due_date, &story.status, today),
+                &story.priority.to_string(),
+                &story.status.to_string(),
+            ]);
+            println!("{}", self.selection.highlight(index, row));
+        }
 
         println!();
         println!();
 
-        println!("[p] previous | [u] update epic | [d] delete epic | [c] create story | [:id:] navigate to story");
+        println!(
+            "[p] previous | [r] rename | [i] edit description | [u] update epic | [!] priority | [o] owner | [O] remove owner | [l] add label | [L] remove label | [t] due date | [T] remove due date | [d] delete epic | [c] create story | [b] board | [h] history | [s] sort by {} | [f] filter by label | [S] filter by status | [/text] search, [/] to clear | [up|down|top :id:] rank story | [rank :id: before :id:] | [:id:] navigate to story | [z] undo | [Z] redo",
+            sort.next().name()
+        );
 
         Ok(())
     }
 
     fn handle_input(&self, input: &str) -> Result<Option<Action>> {
-        let db = self.db.read()?;
-        let stories = db.stories;
         let epic_id = self.epic_id;
         match input {
             "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "r" => Ok(Some(Action::UpdateEpicName { epic_id })),
+            "i" => Ok(Some(Action::UpdateEpicDescription { epic_id })),
             "u" => Ok(Some(Action::UpdateEpicStatus { epic_id })),
+            "!" => Ok(Some(Action::UpdateEpicPriority { epic_id })),
+            "o" => Ok(Some(Action::UpdateEpicOwner { epic_id })),
+            "O" => Ok(Some(Action::RemoveEpicOwner { epic_id })),
+            "l" => Ok(Some(Action::AddEpicLabel { epic_id })),
+            "L" => Ok(Some(Action::RemoveEpicLabel { epic_id })),
+            "f" => Ok(Some(Action::FilterByLabel)),
+            "t" => Ok(Some(Action::UpdateEpicDueDate { epic_id })),
+            "T" => Ok(Some(Action::RemoveEpicDueDate { epic_id })),
+            "s" => {
+                self.sort.set(self.sort.get().next());
+                Ok(None)
+            }
+            "S" => {
+                self.cycle_status_filter();
+                Ok(None)
+            }
             "d" => Ok(Some(Action::DeleteEpic { epic_id })),
             "c" => Ok(Some(Action::CreateStory { epic_id })),
+            "b" => Ok(Some(Action::NavigateToBoard {
+                epic_id: Some(epic_id),
+            })),
+            "h" => Ok(Some(Action::NavigateToEpicHistory { epic_id })),
+            "z" => Ok(Some(Action::Undo)),
+            "Z" => Ok(Some(Action::Redo)),
+            input if input.starts_with('/') => {
+                let text = input[1..].trim();
+                *self.search.borrow_mut() = (!text.is_empty()).then(|| text.to_owned());
+                Ok(None)
+            }
+            input if input.contains(' ') => Ok(self.rank_command(&self.db.snapshot()?, input)),
             input => {
-                let Ok(story_id) = input.parse::<u32>() else {
+                let Some(story_id) = self.db.key_prefix().parse(input) else {
                     return Ok(None);
                 };
 
-                if stories.contains_key(&story_id) {
+                let db_state = self.db.snapshot()?;
+                let is_own_story = db_state
+                    .epics
+                    .get(&epic_id)
+                    .is_some_and(|epic| epic.stories.contains(&story_id));
+                if is_own_story {
                     return Ok(Some(Action::NavigateToStoryDetail { epic_id, story_id }));
                 }
 
@@ -73,6 +305,25 @@ impl Page for EpicDetail {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn rows(&self) -> Result<Vec<Action>> {
+        let db_state = self.db.read()?;
+        let Some(epic) = db_state.epics.get(&self.epic_id) else {
+            return Ok(Vec::new());
+        };
+        Ok(self
+            .story_ids(&db_state, epic)
+            .into_iter()
+            .map(|story_id| Action::NavigateToStoryDetail {
+                epic_id: self.epic_id,
+                story_id,
+            })
+            .collect())
+    }
+
+    fn row_selection(&self) -> Option<&RowSelection> {
+        Some(&self.selection)
+    }
 }
 
 #[cfg(test)]
@@ -80,16 +331,16 @@ mod tests {
     use super::*;
     use crate::{
         db::test_utils::MockDB,
-        models::{Epic, Story},
+        models::{Epic, KeyPrefix},
     };
 
     fn build_page() -> EpicDetail {
         let database = Box::new(MockDB::new());
-        let db = Rc::new(JiraDatabase { database });
+        let db = Rc::new(JiraDatabase::from_database(database));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let epic_id = db.create_epic(epic).unwrap();
 
-        EpicDetail { db, epic_id }
+        EpicDetail::new(db, epic_id)
     }
 
     #[test]
@@ -101,8 +352,8 @@ mod tests {
     #[test]
     fn draw_page_should_throw_error_when_epic_doesnt_exists() {
         let database = Box::new(MockDB::new());
-        let db = Rc::new(JiraDatabase { database });
-        let page = EpicDetail { db, epic_id: 1 };
+        let db = Rc::new(JiraDatabase::from_database(database));
+        let page = EpicDetail::new(db, 1);
         assert!(page.draw_page().is_err());
     }
 
@@ -152,6 +403,51 @@ mod tests {
             Some(Action::UpdateEpicStatus { epic_id }),
         );
 
+        assert_eq!(
+            page.handle_input("r").unwrap(),
+            Some(Action::UpdateEpicName { epic_id }),
+        );
+        assert_eq!(
+            page.handle_input("i").unwrap(),
+            Some(Action::UpdateEpicDescription { epic_id }),
+        );
+
+        assert_eq!(
+            page.handle_input("!").unwrap(),
+            Some(Action::UpdateEpicPriority { epic_id }),
+        );
+
+        assert_eq!(
+            page.handle_input("o").unwrap(),
+            Some(Action::UpdateEpicOwner { epic_id }),
+        );
+        assert_eq!(
+            page.handle_input("O").unwrap(),
+            Some(Action::RemoveEpicOwner { epic_id }),
+        );
+        assert_eq!(
+            page.handle_input("l").unwrap(),
+            Some(Action::AddEpicLabel { epic_id }),
+        );
+        assert_eq!(
+            page.handle_input("L").unwrap(),
+            Some(Action::RemoveEpicLabel { epic_id }),
+        );
+        assert_eq!(
+            page.handle_input("t").unwrap(),
+            Some(Action::UpdateEpicDueDate { epic_id }),
+        );
+        assert_eq!(
+            page.handle_input("T").unwrap(),
+            Some(Action::RemoveEpicDueDate { epic_id }),
+        );
+        assert_eq!(page.handle_input("f").unwrap(), Some(Action::FilterByLabel));
+
+        assert_eq!(page.handle_input("s").unwrap(), None);
+        assert_eq!(page.sort.get(), StorySort::Id);
+        assert_eq!(page.handle_input("S").unwrap(), None);
+        assert_eq!(page.handle_input("/text").unwrap(), None);
+
         let delete_epic = "d";
         assert_eq!(
             page.handle_input(delete_epic).unwrap(),
@@ -164,9 +460,145 @@ mod tests {
             Some(Action::CreateStory { epic_id }),
         );
 
+        assert_eq!(
+            page.handle_input("b").unwrap(),
+            Some(Action::NavigateToBoard {
+                epic_id: Some(epic_id)
+            }),
+        );
+
+        assert_eq!(
+            page.handle_input("h").unwrap(),
+            Some(Action::NavigateToEpicHistory { epic_id }),
+        );
+
         assert_eq!(
             page.handle_input(&story_id.to_string()).unwrap(),
             Some(Action::NavigateToStoryDetail { epic_id, story_id })
         );
+
+        assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+        assert_eq!(page.handle_input("Z").unwrap(), Some(Action::Redo));
+    }
+
+    #[test]
+    fn story_ids_should_only_list_the_epics_own_stories() {
+        let page = build_page();
+        let epic_id = page.epic_id;
+        let create_story = |epic_id: u32, name: &str, description: &str| {
+            let story = Story::new(name.to_owned(), description.to_owned());
+            page.db.create_story(story, epic_id).unwrap()
+        };
+        let login = create_story(epic_id, "Login", "");
+        let checkout = create_story(epic_id, "checkout", "Card form");
+        let billing = create_story(epic_id, "Billing", "Invoices by card");
+        page.db
+            .update_story_status(checkout, Status::InProgress)
+            .unwrap();
+        let other_epic_id = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let other = create_story(other_epic_id, "Other", "");
+        let story_ids = || {
+            let db_state = page.db.read().unwrap();
+            page.story_ids(&db_state, &db_state.epics[&epic_id])
+        };
+
+        assert_eq!(story_ids(), vec![login, checkout, billing]);
+        assert_eq!(page.handle_input(&other.to_string()).unwrap(), None);
+        assert_eq!(
+            page.rows().unwrap()[0],
+            Action::NavigateToStoryDetail {
+                epic_id,
+                story_id: login
+            }
+        );
+
+        page.handle_input("s").unwrap();
+        page.handle_input("s").unwrap();
+        assert_eq!(story_ids(), vec![billing, checkout, login]);
+        page.handle_input("s").unwrap();
+        assert_eq!(story_ids(), vec![login, billing, checkout]);
+
+        page.handle_input("/CARD").unwrap();
+        assert_eq!(story_ids(), vec![billing, checkout]);
+        page.handle_input("S").unwrap();
+        assert_eq!(story_ids(), vec![billing]);
+        page.handle_input("S").unwrap();
+        assert_eq!(story_ids(), vec![checkout]);
+        assert!(page.draw_page().is_ok());
+
+        page.handle_input("/").unwrap();
+        page.handle_input("S").unwrap();
+        page.handle_input("S").unwrap();
+        page.handle_input("S").unwrap();
+        assert_eq!(story_ids(), vec![login, billing, checkout]);
+    }
+
+    #[test]
+    fn handle_input_should_parse_rank_commands() {
+        let page = build_page();
+        let epic_id = page.epic_id;
+        let [story_id, before_id] = [(); 2].map(|_| {
+            let story = Story::new("".to_owned(), "".to_owned());
+            page.db.create_story(story, epic_id).unwrap()
+        });
+        let other_epic_id = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let other = page
+            .db
+            .create_story(Story::new("".to_owned(), "".to_owned()), other_epic_id)
+            .unwrap();
+
+        assert_eq!(
+            page.handle_input(&format!("up {story_id}")).unwrap(),
+            Some(Action::MoveStoryUp { story_id })
+        );
+        assert_eq!(
+            page.handle_input(&format!("down  {story_id}")).unwrap(),
+            Some(Action::MoveStoryDown { story_id })
+        );
+        assert_eq!(
+            page.handle_input(&format!("top {story_id}")).unwrap(),
+            Some(Action::MoveStoryToTop { story_id })
+        );
+        assert_eq!(
+            page.handle_input(&format!("rank {story_id} before {before_id}"))
+                .unwrap(),
+            Some(Action::RankStoryBefore {
+                story_id,
+                before_id
+            })
+        );
+        assert_eq!(page.handle_input(&format!("up {other}")).unwrap(), None);
+        assert_eq!(
+            page.handle_input(&format!("rank {story_id} after {before_id}"))
+                .unwrap(),
+            None
+        );
+        assert_eq!(page.handle_input("top").unwrap(), None);
+    }
+
+    #[test]
+    fn handle_input_should_accept_item_keys() {
+        let mut db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        db.set_key_prefix(KeyPrefix::new("PAY").unwrap());
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let page = EpicDetail::new(Rc::new(db), epic_id);
+
+        assert!(page.draw_page().is_ok());
+        assert_eq!(
+            page.handle_input(&format!("PAY-{story_id}")).unwrap(),
+            Some(Action::NavigateToStoryDetail { epic_id, story_id })
+        );
+        assert_eq!(page.handle_input(&format!("OPS-{story_id}")).unwrap(), None);
     }
 }
@@ -0,0 +1,179 @@
+use anyhow::{anyhow, Result};
+use std::rc::Rc;
+
+use crate::db::JiraDatabase;
+use crate::models::{Action, FieldChange};
+
+use super::{
+    table::{Column, Table},
+    Page,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HistoryItem {
+    Epic(u32),
+    Story(u32),
+}
+
+/// When and by whom an epic or story was created, followed by its field changes, oldest
+/// first.
+pub struct ItemHistory {
+    pub item: HistoryItem,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for ItemHistory {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read()?;
+        let (title, name, created_at, created_by, history) = match self.item {
+            HistoryItem::Epic(epic_id) => {
+                let epic = db_state
+                    .epics
+                    .get(&epic_id)
+                    .ok_or_else(|| anyhow!("Failed to get epic with id: {epic_id}"))?;
+                (
+                    "EPIC",
+                    &epic.name,
+                    epic.created_at,
+                    &epic.created_by,
+                    &epic.history,
+                )
+            }
+            HistoryItem::Story(story_id) => {
+                let story = db_state
+                    .stories
+                    .get(&story_id)
+                    .ok_or_else(|| anyhow!("Failed to get story with id: {story_id}"))?;
+                (
+                    "STORY",
+                    &story.name,
+                    story.created_at,
+                    &story.created_by,
+                    &story.history,
+                )
+            }
+        };
+
+        println!("--------------------------- {title} HISTORY ---------------------------");
+        println!("{name}");
+        match (created_at, created_by) {
+            (Some(created_at), Some(created_by)) => println!(
+                "Created {} by {created_by}",
+                created_at.format("%Y-%m-%d %H:%M")
+            ),
+            _ => println!("Created before history was tracked"),
+        }
+        println!();
+
+        let table = Table::new([
+            Column::fixed("when", 17),
+            Column::fixed("author", 12),
+            Column::fixed("field", 12),
+            Column::wide("change", 29),
+        ]);
+        println!("{}", table.header());
+        for change in history {
+            print_change(&table, change);
+        }
+
+        println!();
+        println!();
+
+        println!("[p] previous | [z] undo | [Z] redo");
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "z" => Ok(Some(Action::Undo)),
+            "Z" => Ok(Some(Action::Redo)),
+            _ => Ok(None),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+fn print_change(table: &Table, change: &FieldChange) {
+    let at = change.at.format("%Y-%m-%d %H:%M").to_string();
+    // Descriptions can span several lines, the table cell can't.
+    let description = format!("{} -> {}", change.from, change.to).replace('\n', " ");
+    println!(
+        "{}",
+        table.row(&[&at, &change.author, &change.field, &description])
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_utils::MockDB,
+        models::{Epic, Status, Story},
+    };
+
+    fn build_db() -> (Rc<JiraDatabase>, u32, u32) {
+        let database = Box::new(MockDB::new());
+        let db = Rc::new(JiraDatabase::from_database(database));
+
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+
+        (db, epic_id, story_id)
+    }
+
+    #[test]
+    fn draw_page_should_not_throw_error() {
+        let (db, epic_id, story_id) = build_db();
+
+        let page = ItemHistory {
+            item: HistoryItem::Epic(epic_id),
+            db: Rc::clone(&db),
+        };
+        assert!(page.draw_page().is_ok());
+
+        let page = ItemHistory {
+            item: HistoryItem::Story(story_id),
+            db,
+        };
+        assert!(page.draw_page().is_ok());
+    }
+
+    #[test]
+    fn draw_page_should_throw_error_when_item_doesnt_exist() {
+        let (db, _, _) = build_db();
+        let page = ItemHistory {
+            item: HistoryItem::Story(999),
+            db,
+        };
+        assert!(page.draw_page().is_err());
+    }
+
+    #[test]
+    fn handle_input_should_return_correct_action() {
+        let (db, epic_id, _) = build_db();
+        let page = ItemHistory {
+            item: HistoryItem::Epic(epic_id),
+            db,
+        };
+
+        assert_eq!(
+            page.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+        assert_eq!(page.handle_input("Z").unwrap(), Some(Action::Redo));
+        assert_eq!(page.handle_input("h").unwrap(), None);
+        assert_eq!(page.handle_input("p\n").unwrap(), None);
+    }
+}
@@ -1,33 +1,91 @@
-use std::rc::Rc;
+use std::{cell::Cell, rc::Rc};
 
 use itertools::Itertools;
 
 use crate::db::JiraDatabase;
-use crate::models::Action;
+use crate::io_utils;
+use crate::models::{Action, DBState};
 
-use super::{page_helpers::get_column_string, Page};
+use super::{
+    page_helpers::{due_date_column, labels_column, LabelFilter, RowSelection, SortOrder},
+    table::{Column, Table},
+    Page,
+};
 
 pub struct HomePage {
     pub db: Rc<JiraDatabase>,
+    sort: Cell<SortOrder>,
+    label_filter: LabelFilter,
+    selection: RowSelection,
+}
+
+impl HomePage {
+    pub fn new(db: Rc<JiraDatabase>) -> Self {
+        Self {
+            db,
+            sort: Cell::default(),
+            label_filter: LabelFilter::default(),
+            selection: RowSelection::default(),
+        }
+    }
+
+    /// Only lists the epics carrying `label`, all of them for `None`.
+    pub fn filter_by_label(&self, label: Option<String>) {
+        self.label_filter.set(label);
+    }
+
+    /// The listed epics, in table order.
+    fn epic_ids(&self, db_state: &DBState) -> Vec<u32> {
+        let sort = self.sort.get();
+        db_state
+            .epics
+            .iter()
+            .filter(|(_, epic)| self.label_filter.matches(&epic.labels))
+            .sorted_by_key(|(id, epic)| sort.sort_key(**id, epic.priority))
+            .map(|(id, _)| *id)
+            .collect()
+    }
 }
 
 impl Page for HomePage {
     fn draw_page(&self) -> anyhow::Result<()> {
         let db_state = self.db.read()?;
+        let keys = self.db.key_prefix();
+        let sort = self.sort.get();
+        let today = io_utils::today();
         println!("----------------------------- EPICS -----------------------------");
-        println!("     id     |               name               |      status      ");
-        db_state.epics.keys().sorted().for_each(|epic_id| {
-            let epic = &db_state.epics[epic_id];
-            let epic_id = get_column_string(&epic_id.to_string(), 11);
-            let epic_name = get_column_string(&epic.name, 32);
-            let epic_status = get_column_string(&epic.status.to_string(), 17);
-            println!("{} | {} | {}", epic_id, epic_name, epic_status);
-        });
+        self.label_filter.draw();
+        let table = Table::new([
+            Column::fixed("id", 11),
+            Column::wide("name", 18),
+            Column::fixed("owner", 10),
+            Column::wide("labels", 14).optional(),
+            Column::fixed("due", 12).optional(),
+            Column::fixed("priority", 9),
+            Column::fixed("status", 13),
+        ]);
+        println!("{}", table.header());
+        for (index, epic_id) in self.epic_ids(&db_state).into_iter().enumerate() {
+            let epic = &db_state.epics[&epic_id];
+            let row = table.row(&[
+                &keys.key(epic_id),
+                &epic.name,
+                epic.owner.as_deref().unwrap_or(""),
+                &labels_column(&epic.labels),
+                &due_date_column(epic.due_date, &epic.status, today),
+                &epic.priority.to_string(),
+                &epic.status.to_string(),
+            ]);
+            println!("{}", self.selection.highlight(index, row));
+        }
 
         println!();
         println!();
 
-        println!("[q] quit | [c] create epic | [:id:] navigate to epic");
+        println!(
+            "[q] quit | [c] create epic | [w] my work | [u] upcoming deadlines | [b] board | [t] trash | {} | [f] filter by label | [:id:] navigate to epic | [z] undo | [Z] redo",
+            sort.toggle_hint()
+        );
 
         Ok(())
     }
@@ -36,14 +94,25 @@ impl Page for HomePage {
         match input {
             "q" => Ok(Some(Action::Exit)),
             "c" => Ok(Some(Action::CreateEpic)),
+            "t" => Ok(Some(Action::NavigateToTrash)),
+            "w" => Ok(Some(Action::NavigateToMyWork)),
+            "u" => Ok(Some(Action::NavigateToDeadlines)),
+            "b" => Ok(Some(Action::NavigateToBoard { epic_id: None })),
+            "f" => Ok(Some(Action::FilterByLabel)),
+            "s" => {
+                self.sort.set(self.sort.get().toggled());
+                Ok(None)
+            }
+            "z" => Ok(Some(Action::Undo)),
+            "Z" => Ok(Some(Action::Redo)),
             input => {
-                let db_state = &self.db.read()?;
-                let Ok(epic_id) = input.parse::<u32>() else {
+                let db_state = &self.db.snapshot()?;
+                let Some(epic_id) = self.db.key_prefix().parse(input) else {
                     return Ok(None);
                 };
 
                 if db_state.epics.contains_key(&epic_id) {
-                    return Ok(Some(Action::NavigateToEpicDetail { epic_id: epic_id }));
+                    return Ok(Some(Action::NavigateToEpicDetail { epic_id }));
                 }
 
                 Ok(None)
@@ -54,6 +123,18 @@ impl Page for HomePage {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn rows(&self) -> anyhow::Result<Vec<Action>> {
+        let epic_ids = self.epic_ids(&self.db.read()?);
+        Ok(epic_ids
+            .into_iter()
+            .map(|epic_id| Action::NavigateToEpicDetail { epic_id })
+            .collect())
+    }
+
+    fn row_selection(&self) -> Option<&RowSelection> {
+        Some(&self.selection)
+    }
 }
 
 #[cfg(test)]
@@ -63,15 +144,13 @@ mod tests {
 
     use crate::{
         db::{test_utils::MockDB, JiraDatabase},
-        models::Epic,
+        models::{Epic, KeyPrefix},
     };
 
     fn build_page() -> HomePage {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::from_database(Box::new(MockDB::new())));
 
-        HomePage { db }
+        HomePage::new(db)
     }
 
     #[test]
@@ -115,11 +194,91 @@ mod tests {
         assert_eq!(page.handle_input(quit_input).unwrap(), Some(Action::Exit));
         assert_eq!(
             page.handle_input(&epic_id.to_string()).unwrap(),
-            Some(Action::NavigateToEpicDetail { epic_id: epic_id })
+            Some(Action::NavigateToEpicDetail { epic_id })
         );
         assert_eq!(
             page.handle_input(crete_epic_input).unwrap(),
             Some(Action::CreateEpic)
         );
+        assert_eq!(
+            page.handle_input("t").unwrap(),
+            Some(Action::NavigateToTrash)
+        );
+        assert_eq!(
+            page.handle_input("w").unwrap(),
+            Some(Action::NavigateToMyWork)
+        );
+        assert_eq!(
+            page.handle_input("u").unwrap(),
+            Some(Action::NavigateToDeadlines)
+        );
+        assert_eq!(
+            page.handle_input("b").unwrap(),
+            Some(Action::NavigateToBoard { epic_id: None })
+        );
+        assert_eq!(page.handle_input("f").unwrap(), Some(Action::FilterByLabel));
+        assert_eq!(page.handle_input("s").unwrap(), None);
+        assert_eq!(page.sort.get(), SortOrder::Priority);
+        assert!(page.draw_page().is_ok());
+        assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+        assert_eq!(page.handle_input("Z").unwrap(), Some(Action::Redo));
+    }
+
+    #[test]
+    fn filter_by_label_should_only_list_labelled_epics() {
+        let page = build_page();
+        let labelled = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let other = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        page.db.add_epic_label(labelled, "backend").unwrap();
+
+        page.filter_by_label(Some("backend".to_owned()));
+        assert_eq!(page.epic_ids(&page.db.read().unwrap()), vec![labelled]);
+        assert!(page.draw_page().is_ok());
+
+        page.filter_by_label(None);
+        assert_eq!(
+            page.epic_ids(&page.db.read().unwrap()),
+            vec![labelled, other]
+        );
+    }
+
+    #[test]
+    fn rows_should_open_the_listed_epics() {
+        let page = build_page();
+        let epic_ids = [(); 2].map(|_| {
+            page.db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap()
+        });
+
+        assert_eq!(
+            page.rows().unwrap(),
+            epic_ids.map(|epic_id| Action::NavigateToEpicDetail { epic_id })
+        );
+        page.row_selection().unwrap().step(1, 2);
+        assert!(page.draw_page().is_ok());
+    }
+
+    #[test]
+    fn handle_input_should_accept_item_keys() {
+        let mut db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        db.set_key_prefix(KeyPrefix::new("PAY").unwrap());
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let page = HomePage::new(Rc::new(db));
+
+        assert!(page.draw_page().is_ok());
+        assert_eq!(
+            page.handle_input(&format!("pay-{epic_id}")).unwrap(),
+            Some(Action::NavigateToEpicDetail { epic_id })
+        );
+        assert_eq!(page.handle_input(&format!("OPS-{epic_id}")).unwrap(), None);
     }
 }
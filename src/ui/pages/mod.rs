@@ -2,15 +2,43 @@ use crate::models::Action;
 use anyhow::Result;
 use std::any::Any;
 
+mod board_page;
+mod deadlines_page;
 mod epic_detail_page;
+mod history_page;
 mod home_page;
+mod my_work_page;
 mod page_helpers;
+mod project_picker_page;
 mod story_detail_page;
+mod table;
+mod trash_page;
 
-pub use self::{epic_detail_page::EpicDetail, home_page::HomePage, story_detail_page::StoryDetail};
+pub use self::{
+    board_page::Board,
+    deadlines_page::Deadlines,
+    epic_detail_page::EpicDetail,
+    history_page::{HistoryItem, ItemHistory},
+    home_page::HomePage,
+    my_work_page::MyWork,
+    page_helpers::RowSelection,
+    project_picker_page::ProjectPicker,
+    story_detail_page::StoryDetail,
+    trash_page::TrashBin,
+};
 
 pub trait Page {
     fn draw_page(&self) -> Result<()>;
     fn handle_input(&self, input: &str) -> Result<Option<Action>>;
     fn as_any(&self) -> &dyn Any;
+
+    /// What opening each row of the page's list does, in the order the rows are drawn.
+    fn rows(&self) -> Result<Vec<Action>> {
+        Ok(Vec::new())
+    }
+
+    /// The row picked with the arrow keys in the full-screen mode, for pages with rows.
+    fn row_selection(&self) -> Option<&RowSelection> {
+        None
+    }
 }
@@ -0,0 +1,216 @@
+use anyhow::Result;
+use itertools::Itertools;
+use std::rc::Rc;
+
+use crate::db::JiraDatabase;
+use crate::models::{Action, DBState, Status};
+
+use super::{
+    table::{Column, Table},
+    Page,
+};
+
+/// The epics owned by and the stories assigned to the current user, most urgent first.
+/// Closed items are left out.
+pub struct MyWork {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl MyWork {
+    fn epic_ids(&self, db_state: &DBState) -> Vec<u32> {
+        let username = self.db.current_user();
+        db_state
+            .epics
+            .iter()
+            .filter(|(_, epic)| epic.owner.as_deref() == Some(username))
+            .filter(|(_, epic)| epic.status != Status::Closed)
+            .sorted_by_key(|(id, epic)| (epic.priority, **id))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// With the epic each story belongs to.
+    fn story_ids(&self, db_state: &DBState) -> Vec<(u32, u32)> {
+        let username = self.db.current_user();
+        db_state
+            .epics
+            .iter()
+            .flat_map(|(epic_id, epic)| epic.stories.iter().map(|story_id| (*story_id, *epic_id)))
+            .filter_map(|(story_id, epic_id)| {
+                let story = db_state.stories.get(&story_id)?;
+                let assigned = story.assignee.as_deref() == Some(username);
+                (assigned && story.status != Status::Closed).then_some((story_id, epic_id, story))
+            })
+            .sorted_by_key(|(story_id, _, story)| (story.priority, *story_id))
+            .map(|(story_id, epic_id, _)| (story_id, epic_id))
+            .collect()
+    }
+}
+
+impl Page for MyWork {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read()?;
+        let keys = self.db.key_prefix();
+        let username = self.db.current_user();
+
+        println!("---------------------------- MY WORK ----------------------------");
+        match db_state.users.get(username) {
+            Some(user) if !user.name.is_empty() => println!("{username} ({})", user.name),
+            Some(_) => println!("{username}"),
+            None => println!("{username} is not one of this board's users"),
+        }
+        println!();
+
+        let table = Table::new([
+            Column::fixed("id", 11),
+            Column::wide("name", 24),
+            Column::fixed("priority", 9),
+            Column::fixed("status", 13),
+        ]);
+
+        println!("----------------------------- EPICS -----------------------------");
+        println!("{}", table.header());
+        for epic_id in self.epic_ids(&db_state) {
+            let epic = &db_state.epics[&epic_id];
+            let row = table.row(&[
+                &keys.key(epic_id),
+                &epic.name,
+                &epic.priority.to_string(),
+                &epic.status.to_string(),
+            ]);
+            println!("{row}");
+        }
+
+        println!();
+
+        println!("---------------------------- STORIES ----------------------------");
+        println!("{}", table.header());
+        for (story_id, _) in self.story_ids(&db_state) {
+            let story = &db_state.stories[&story_id];
+            let row = table.row(&[
+                &keys.key(story_id),
+                &story.name,
+                &story.priority.to_string(),
+                &story.status.to_string(),
+            ]);
+            println!("{row}");
+        }
+
+        println!();
+        println!();
+
+        println!("[p] previous | [:id:] navigate to epic or story | [z] undo | [Z] redo");
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "z" => Ok(Some(Action::Undo)),
+            "Z" => Ok(Some(Action::Redo)),
+            input => {
+                let Some(id) = self.db.key_prefix().parse(input) else {
+                    return Ok(None);
+                };
+
+                let db_state = self.db.snapshot()?;
+                if self.epic_ids(&db_state).contains(&id) {
+                    return Ok(Some(Action::NavigateToEpicDetail { epic_id: id }));
+                }
+                let story = self
+                    .story_ids(&db_state)
+                    .into_iter()
+                    .find(|(story_id, _)| *story_id == id);
+                Ok(story
+                    .map(|(story_id, epic_id)| Action::NavigateToStoryDetail { epic_id, story_id }))
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_utils::MockDB,
+        models::{Epic, Priority, Story, User},
+    };
+
+    fn build_page() -> MyWork {
+        let mut db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        db.set_current_user("alice".to_owned());
+        db.add_user("alice", User::default()).unwrap();
+        db.add_user("bob", User::default()).unwrap();
+
+        MyWork { db: Rc::new(db) }
+    }
+
+    fn create_story(page: &MyWork, epic_id: u32, assignee: &str, priority: Priority) -> u32 {
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        story.priority = priority;
+        let story_id = page.db.create_story(story, epic_id).unwrap();
+        page.db
+            .assign_story(story_id, Some(assignee.to_owned()))
+            .unwrap();
+        story_id
+    }
+
+    #[test]
+    fn story_ids_should_list_open_work_of_the_current_user_by_priority() {
+        let page = build_page();
+        let epic_id = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let low = create_story(&page, epic_id, "alice", Priority::Low);
+        let high = create_story(&page, epic_id, "alice", Priority::High);
+        create_story(&page, epic_id, "bob", Priority::Highest);
+        let closed = create_story(&page, epic_id, "alice", Priority::Highest);
+        page.db.update_story_status(closed, Status::Closed).unwrap();
+
+        let db_state = page.db.read().unwrap();
+        assert_eq!(
+            page.story_ids(&db_state),
+            vec![(high, epic_id), (low, epic_id)]
+        );
+        assert!(page.epic_ids(&db_state).is_empty());
+        assert!(page.draw_page().is_ok());
+    }
+
+    #[test]
+    fn handle_input_should_navigate_to_listed_items() {
+        let page = build_page();
+        let epic_id = page
+            .db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        page.db
+            .update_epic_owner(epic_id, Some("alice".to_owned()))
+            .unwrap();
+        let story_id = create_story(&page, epic_id, "alice", Priority::Medium);
+        let other_story_id = create_story(&page, epic_id, "bob", Priority::Medium);
+
+        assert_eq!(
+            page.handle_input(&epic_id.to_string()).unwrap(),
+            Some(Action::NavigateToEpicDetail { epic_id })
+        );
+        assert_eq!(
+            page.handle_input(&story_id.to_string()).unwrap(),
+            Some(Action::NavigateToStoryDetail { epic_id, story_id })
+        );
+        assert_eq!(
+            page.handle_input(&other_story_id.to_string()).unwrap(),
+            None
+        );
+        assert_eq!(
+            page.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+    }
+}
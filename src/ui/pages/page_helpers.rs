@@ -1,64 +1,181 @@
-use ellipse::Ellipse;
-
-pub fn get_column_string(text: &str, width: usize) -> String {
-    let len = text.len();
-    match len.cmp(&width) {
-        std::cmp::Ordering::Less => {
-            let left_over = width - len;
-            let mut column_string = text.to_owned();
-
-            for _ in 0..left_over {
-                column_string.push(' ');
-            }
-            column_string
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+};
+
+use chrono::NaiveDate;
+use crossterm::style::Stylize;
+use itertools::Itertools;
+
+use crate::models::{is_overdue, Priority, Status};
+
+/// Order of the item tables, toggled with `[s]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SortOrder {
+    #[default]
+    Id,
+    Priority,
+}
+
+impl SortOrder {
+    pub fn toggled(self) -> Self {
+        match self {
+            SortOrder::Id => SortOrder::Priority,
+            SortOrder::Priority => SortOrder::Id,
+        }
+    }
+
+    /// Items with the same priority stay in id order.
+    pub fn sort_key(self, id: u32, priority: Priority) -> (Option<Priority>, u32) {
+        match self {
+            SortOrder::Id => (None, id),
+            SortOrder::Priority => (Some(priority), id),
         }
-        std::cmp::Ordering::Equal => text.to_owned(),
-        std::cmp::Ordering::Greater => {
-            if width == 0 {
-                "".to_owned()
-            } else if width == 1 {
-                ".".to_owned()
-            } else if width == 2 {
-                "..".to_owned()
-            } else if width == 3 {
-                "...".to_owned()
-            } else {
-                text.truncate_ellipse(width - 3).to_string()
-            }
+    }
+
+    /// The `[s]` hint, naming the order it switches to.
+    pub fn toggle_hint(self) -> &'static str {
+        match self {
+            SortOrder::Id => "[s] sort by priority",
+            SortOrder::Priority => "[s] sort by id",
         }
     }
 }
 
+/// The label the item tables are narrowed to, picked with `[f]`.
+#[derive(Default)]
+pub struct LabelFilter(RefCell<Option<String>>);
+
+impl LabelFilter {
+    /// `None` shows every item again.
+    pub fn set(&self, label: Option<String>) {
+        *self.0.borrow_mut() = label;
+    }
+
+    pub fn matches(&self, labels: &BTreeSet<String>) -> bool {
+        match &*self.0.borrow() {
+            Some(label) => labels.contains(label),
+            None => true,
+        }
+    }
+
+    /// Prints which label is picked, if any.
+    pub fn draw(&self) {
+        if let Some(label) = &*self.0.borrow() {
+            println!("Only showing items labelled {label}");
+            println!();
+        }
+    }
+}
+
+/// The picked row of a page's list. Nothing is picked in the line mode.
+#[derive(Default)]
+pub struct RowSelection(Cell<Option<usize>>);
+
+impl RowSelection {
+    pub fn get(&self) -> Option<usize> {
+        self.0.get()
+    }
+
+    /// Moves the selection by `offset` rows, staying within the `count` rows of the list.
+    /// Without a selection yet, the first row is picked.
+    pub fn step(&self, offset: isize, count: usize) {
+        let row = match (self.0.get(), count) {
+            (_, 0) => None,
+            (None, _) => Some(0),
+            (Some(row), count) => Some(row.saturating_add_signed(offset).min(count - 1)),
+        };
+        self.0.set(row);
+    }
+
+    /// Picks `row`, for pages where the selection follows an item that changes rows.
+    pub fn set(&self, row: Option<usize>) {
+        self.0.set(row);
+    }
+
+    /// Shows the `index`th row in reverse video when it is the picked one.
+    pub fn highlight(&self, index: usize, row: String) -> String {
+        match self.0.get() == Some(index) {
+            true => row.reverse().to_string(),
+            false => row,
+        }
+    }
+}
+
+pub fn labels_column(labels: &BTreeSet<String>) -> String {
+    labels.iter().join(", ")
+}
+
+/// The due date, marked with `!` when the item is overdue.
+pub fn due_date_column(due_date: Option<NaiveDate>, status: &Status, today: NaiveDate) -> String {
+    match due_date {
+        Some(date) if is_overdue(due_date, status, today) => format!("{date} !"),
+        Some(date) => date.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Prints a description below the item's row, line by line as it was written.
+pub fn print_description(description: &str) {
+    if description.is_empty() {
+        return;
+    }
+    println!();
+    for line in description.lines() {
+        println!("  {line}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_get_column_string() {
-        let text1 = "";
-        let text2 = "test";
-        let text3 = "testme";
-        let text4 = "testmetest";
-
-        let width = 0;
-        assert_eq!(get_column_string(text4, width), "".to_owned());
+    fn row_selection_should_stay_within_the_list() {
+        let selection = RowSelection::default();
+        selection.step(1, 3);
+        assert_eq!(selection.get(), Some(0));
+        selection.step(-1, 3);
+        assert_eq!(selection.get(), Some(0));
+        selection.step(5, 3);
+        assert_eq!(selection.get(), Some(2));
+        selection.step(0, 2);
+        assert_eq!(selection.get(), Some(1));
+        assert_ne!(selection.highlight(1, "row".to_owned()), "row");
+        assert_eq!(selection.highlight(0, "row".to_owned()), "row");
+        selection.step(0, 0);
+        assert_eq!(selection.get(), None);
+    }
 
-        let width = 1;
-        assert_eq!(get_column_string(text4, width), ".".to_owned());
+    #[test]
+    fn label_filter_should_match_items_with_the_label() {
+        let labels = BTreeSet::from_iter(["backend".to_owned(), "tech-debt".to_owned()]);
+        let filter = LabelFilter::default();
+        assert!(filter.matches(&labels));
+        assert!(filter.matches(&BTreeSet::new()));
 
-        let width = 2;
-        assert_eq!(get_column_string(text4, width), "..".to_owned());
+        filter.set(Some("backend".to_owned()));
+        assert!(filter.matches(&labels));
+        assert!(!filter.matches(&BTreeSet::new()));
 
-        let width = 3;
-        assert_eq!(get_column_string(text4, width), "...".to_owned());
+        filter.set(Some("customer-x".to_owned()));
+        assert!(!filter.matches(&labels));
+        assert_eq!(labels_column(&labels), "backend, tech-debt");
+    }
 
-        let width = 4;
-        assert_eq!(get_column_string(text4, width), "t...".to_owned());
+    #[test]
+    fn due_date_column_should_mark_overdue_items() {
+        let today = NaiveDate::from_ymd_opt(2023, 5, 17).unwrap();
+        let yesterday = NaiveDate::from_ymd_opt(2023, 5, 16);
 
-        let width = 6;
-        assert_eq!(get_column_string(text1, width), "      ".to_owned());
-        assert_eq!(get_column_string(text2, width), "test  ".to_owned());
-        assert_eq!(get_column_string(text3, width), "testme".to_owned());
-        assert_eq!(get_column_string(text4, width), "tes...".to_owned());
+        assert_eq!(
+            due_date_column(yesterday, &Status::Open, today),
+            "2023-05-16 !"
+        );
+        assert_eq!(
+            due_date_column(yesterday, &Status::Closed, today),
+            "2023-05-16"
+        );
+        assert_eq!(due_date_column(None, &Status::Open, today), "");
     }
 }
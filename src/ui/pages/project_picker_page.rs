@@ -0,0 +1,98 @@
+use anyhow::Result;
+
+use crate::models::Action;
+
+use super::{
+    table::{Column, Table},
+    Page,
+};
+
+/// Shown before the home page when several projects are configured and none was picked.
+pub struct ProjectPicker {
+    pub projects: Vec<String>,
+}
+
+impl Page for ProjectPicker {
+    fn draw_page(&self) -> Result<()> {
+        println!("--------------------------- PROJECTS ----------------------------");
+        let table = Table::new([Column::fixed("#", 11), Column::wide("name", 24)]);
+        println!("{}", table.header());
+        for (index, name) in self.projects.iter().enumerate() {
+            println!("{}", table.row(&[&(index + 1).to_string(), name]));
+        }
+
+        println!();
+        println!();
+
+        println!("[q] quit | [:#: or :name:] open project");
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        if input == "q" {
+            return Ok(Some(Action::Exit));
+        }
+
+        let by_number = input
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| number.checked_sub(1))
+            .and_then(|index| self.projects.get(index));
+        let by_name = self.projects.iter().find(|name| *name == input);
+
+        Ok(by_number
+            .or(by_name)
+            .map(|name| Action::OpenProject { name: name.clone() }))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_page() -> ProjectPicker {
+        ProjectPicker {
+            projects: vec!["payments".to_owned(), "platform".to_owned()],
+        }
+    }
+
+    #[test]
+    fn draw_page_should_not_throw_error() {
+        assert!(build_page().draw_page().is_ok());
+    }
+
+    #[test]
+    fn handle_input_should_not_throw_on_invalid_input() {
+        let page = build_page();
+
+        assert!(page.handle_input("").unwrap().is_none());
+        assert!(page.handle_input("0").unwrap().is_none());
+        assert!(page.handle_input("3").unwrap().is_none());
+        assert!(page.handle_input("pay").unwrap().is_none());
+        assert!(page.handle_input("q\n").unwrap().is_none());
+    }
+
+    #[test]
+    fn handle_input_should_return_correct_action() {
+        let page = build_page();
+
+        assert_eq!(page.handle_input("q").unwrap(), Some(Action::Exit));
+        assert_eq!(
+            page.handle_input("2").unwrap(),
+            Some(Action::OpenProject {
+                name: "platform".to_owned()
+            })
+        );
+        assert_eq!(
+            page.handle_input("payments").unwrap(),
+            Some(Action::OpenProject {
+                name: "payments".to_owned()
+            })
+        );
+    }
+}
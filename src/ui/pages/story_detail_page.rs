@@ -2,9 +2,14 @@ use anyhow::{anyhow, Result};
 use std::rc::Rc;
 
 use crate::db::JiraDatabase;
+use crate::io_utils;
 use crate::models::Action;
 
-use super::{page_helpers::get_column_string, Page};
+use super::{
+    page_helpers::{due_date_column, labels_column, print_description},
+    table::{Column, Table},
+    Page,
+};
 
 pub struct StoryDetail {
     pub epic_id: u32,
@@ -21,17 +26,59 @@ impl Page for StoryDetail {
             .ok_or_else(|| anyhow!(format!("Failed to get story with id: {}", self.story_id)))?;
 
         println!("------------------------------ STORY ------------------------------");
-        println!("  id  |     name     |         description         |    status    ");
-        let story_id = get_column_string(&self.story_id.to_string(), 5);
-        let story_name = get_column_string(&story.name, 12);
-        let story_description = get_column_string(&story.description, 27);
-        let story_status = get_column_string(&story.status.to_string(), 13);
-        println!("{story_id} | {story_name} | {story_description} |{story_status}");
+        let table = Table::new([
+            Column::fixed("id", 9),
+            Column::wide("name", 26),
+            Column::fixed("assignee", 10),
+            Column::fixed("priority", 9),
+            Column::fixed("status", 13),
+        ]);
+        println!("{}", table.header());
+        println!(
+            "{}",
+            table.row(&[
+                &self.db.key_prefix().key(self.story_id),
+                &story.name,
+                story.assignee.as_deref().unwrap_or(""),
+                &story.priority.to_string(),
+                &story.status.to_string(),
+            ])
+        );
+        if !story.labels.is_empty() {
+            println!("labels: {}", labels_column(&story.labels));
+        }
+        if story.due_date.is_some() {
+            let due_date = due_date_column(story.due_date, &story.status, io_utils::today());
+            println!("due: {due_date}");
+        }
+        print_description(&story.description);
+
+        println!();
+
+        println!("---------------------------- COMMENTS ----------------------------");
+        if story.comments.is_empty() {
+            println!("No comments yet");
+        }
+        for comment in &story.comments {
+            let edited = if comment.edited_at.is_some() {
+                " (edited)"
+            } else {
+                ""
+            };
+            println!(
+                "#{} {} on {}{edited}",
+                comment.id,
+                comment.author,
+                comment.created_at.format("%Y-%m-%d %H:%M")
+            );
+            println!("{}", comment.body);
+            println!();
+        }
 
         println!();
         println!();
 
-        println!("[p] previous | [u] update story | [d] delete story");
+        println!("[p] previous | [r] rename | [i] edit description | [u] update story | [!] priority | [a] assign | [A] unassign | [l] add label | [L] remove label | [t] due date | [T] remove due date | [m] comment | [e] edit comment | [x] delete comment | [v] move to epic | [d] delete story | [h] history | [z] undo | [Z] redo");
 
         Ok(())
     }
@@ -39,13 +86,58 @@ impl Page for StoryDetail {
     fn handle_input(&self, input: &str) -> Result<Option<Action>> {
         match input {
             "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "r" => Ok(Some(Action::UpdateStoryName {
+                story_id: self.story_id,
+            })),
+            "i" => Ok(Some(Action::UpdateStoryDescription {
+                story_id: self.story_id,
+            })),
             "u" => Ok(Some(Action::UpdateStoryStatus {
                 story_id: self.story_id,
+                to: None,
+            })),
+            "!" => Ok(Some(Action::UpdateStoryPriority {
+                story_id: self.story_id,
+            })),
+            "a" => Ok(Some(Action::AssignStory {
+                story_id: self.story_id,
+            })),
+            "A" => Ok(Some(Action::UnassignStory {
+                story_id: self.story_id,
+            })),
+            "l" => Ok(Some(Action::AddStoryLabel {
+                story_id: self.story_id,
+            })),
+            "L" => Ok(Some(Action::RemoveStoryLabel {
+                story_id: self.story_id,
+            })),
+            "t" => Ok(Some(Action::UpdateStoryDueDate {
+                story_id: self.story_id,
+            })),
+            "T" => Ok(Some(Action::RemoveStoryDueDate {
+                story_id: self.story_id,
+            })),
+            "m" => Ok(Some(Action::CommentOnStory {
+                story_id: self.story_id,
+            })),
+            "e" => Ok(Some(Action::EditComment {
+                story_id: self.story_id,
+            })),
+            "x" => Ok(Some(Action::DeleteComment {
+                story_id: self.story_id,
+            })),
+            "v" => Ok(Some(Action::MoveStory {
+                story_id: self.story_id,
             })),
             "d" => Ok(Some(Action::DeleteStory {
                 epic_id: self.epic_id,
                 story_id: self.story_id,
             })),
+            "h" => Ok(Some(Action::NavigateToStoryHistory {
+                story_id: self.story_id,
+            })),
+            "z" => Ok(Some(Action::Undo)),
+            "Z" => Ok(Some(Action::Redo)),
             _ => Ok(None),
         }
     }
@@ -65,7 +157,7 @@ mod tests {
 
     fn build_page() -> StoryDetail {
         let database = Box::new(MockDB::new());
-        let db = Rc::new(JiraDatabase { database });
+        let db = Rc::new(JiraDatabase::from_database(database));
 
         let epic = Epic::new("".to_owned(), "".to_owned());
         let epic_id = db.create_epic(epic).unwrap();
@@ -84,12 +176,15 @@ mod tests {
     fn draw_page_should_not_throw_error() {
         let page = build_page();
         assert!(page.draw_page().is_ok());
+
+        page.db.add_comment(page.story_id, "Looks good").unwrap();
+        assert!(page.draw_page().is_ok());
     }
 
     #[test]
     fn draw_page_should_throw_error_when_epic_doesnt_exists() {
         let database = Box::new(MockDB::new());
-        let db = Rc::new(JiraDatabase { database });
+        let db = Rc::new(JiraDatabase::from_database(database));
         let page = StoryDetail {
             db,
             epic_id: 1,
@@ -131,7 +226,63 @@ mod tests {
         let update_epic = "u";
         assert_eq!(
             page.handle_input(update_epic).unwrap(),
-            Some(Action::UpdateStoryStatus { story_id }),
+            Some(Action::UpdateStoryStatus { story_id, to: None }),
+        );
+
+        assert_eq!(
+            page.handle_input("r").unwrap(),
+            Some(Action::UpdateStoryName { story_id }),
+        );
+        assert_eq!(
+            page.handle_input("i").unwrap(),
+            Some(Action::UpdateStoryDescription { story_id }),
+        );
+
+        assert_eq!(
+            page.handle_input("!").unwrap(),
+            Some(Action::UpdateStoryPriority { story_id }),
+        );
+
+        assert_eq!(
+            page.handle_input("a").unwrap(),
+            Some(Action::AssignStory { story_id }),
+        );
+        assert_eq!(
+            page.handle_input("A").unwrap(),
+            Some(Action::UnassignStory { story_id }),
+        );
+        assert_eq!(
+            page.handle_input("l").unwrap(),
+            Some(Action::AddStoryLabel { story_id }),
+        );
+        assert_eq!(
+            page.handle_input("L").unwrap(),
+            Some(Action::RemoveStoryLabel { story_id }),
+        );
+        assert_eq!(
+            page.handle_input("t").unwrap(),
+            Some(Action::UpdateStoryDueDate { story_id }),
+        );
+        assert_eq!(
+            page.handle_input("T").unwrap(),
+            Some(Action::RemoveStoryDueDate { story_id }),
+        );
+        assert_eq!(
+            page.handle_input("m").unwrap(),
+            Some(Action::CommentOnStory { story_id }),
+        );
+        assert_eq!(
+            page.handle_input("e").unwrap(),
+            Some(Action::EditComment { story_id }),
+        );
+        assert_eq!(
+            page.handle_input("x").unwrap(),
+            Some(Action::DeleteComment { story_id }),
+        );
+
+        assert_eq!(
+            page.handle_input("v").unwrap(),
+            Some(Action::MoveStory { story_id }),
         );
 
         let delete_epic = "d";
@@ -139,5 +290,13 @@ mod tests {
             page.handle_input(delete_epic).unwrap(),
             Some(Action::DeleteStory { epic_id, story_id }),
         );
+
+        assert_eq!(
+            page.handle_input("h").unwrap(),
+            Some(Action::NavigateToStoryHistory { story_id }),
+        );
+
+        assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+        assert_eq!(page.handle_input("Z").unwrap(), Some(Action::Redo));
     }
 }
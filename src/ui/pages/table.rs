@@ -0,0 +1,305 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::io_utils;
+
+/// What is put between two cells of a row.
+const SEPARATOR: &str = " | ";
+/// Marks text cut to fit its column.
+const ELLIPSIS: &str = "...";
+/// How narrow wide columns get before other columns make room on narrow terminals.
+const MIN_WIDE_WIDTH: usize = 8;
+
+/// A column of a [`Table`], by its title and how wide its cells are.
+pub struct Column {
+    title: String,
+    width: usize,
+    wide: bool,
+    optional: bool,
+    hidden: bool,
+}
+
+impl Column {
+    /// Always `width` columns of the terminal, for ids, dates and the like.
+    pub fn fixed(title: impl Into<String>, width: usize) -> Self {
+        Self {
+            title: title.into(),
+            width,
+            wide: false,
+            optional: false,
+            hidden: false,
+        }
+    }
+
+    /// At least `width` columns, and a share of what the terminal has left, for names.
+    pub fn wide(title: impl Into<String>, width: usize) -> Self {
+        Self {
+            title: title.into(),
+            width,
+            wide: true,
+            optional: false,
+            hidden: false,
+        }
+    }
+
+    /// Left out when the terminal is too narrow, for details like labels and due dates.
+    pub fn optional(self) -> Self {
+        Self {
+            optional: true,
+            ..self
+        }
+    }
+}
+
+/// Lays out the rows of the pages' lists, sized to the terminal. Text is measured in the
+/// columns it takes on screen, so accents, CJK and emoji line up.
+pub struct Table {
+    columns: Vec<Column>,
+}
+
+impl Table {
+    pub fn new(columns: impl IntoIterator<Item = Column>) -> Self {
+        Self::with_width(columns, io_utils::terminal_width())
+    }
+
+    /// Wide columns share what `width` has left after all columns got their minimum.
+    /// When the minimums don't fit, wide columns narrow down to `MIN_WIDE_WIDTH`, then
+    /// optional columns are left out and at last the columns on the right are cut, so no
+    /// row is wider than `width`.
+    fn with_width(columns: impl IntoIterator<Item = Column>, width: usize) -> Self {
+        let mut table = Self {
+            columns: columns.into_iter().collect(),
+        };
+
+        let mut excess = table.width().saturating_sub(width);
+        for column in table.columns.iter_mut().rev().filter(|column| column.wide) {
+            let cut = excess.min(column.width.saturating_sub(MIN_WIDE_WIDTH));
+            column.width -= cut;
+            excess -= cut;
+        }
+        while table.width() > width {
+            let optional = table.shown_mut().rev().find(|column| column.optional);
+            let Some(column) = optional else {
+                break;
+            };
+            column.hidden = true;
+        }
+        while table.width() > width {
+            let excess = table.width() - width;
+            let shown = table.shown().count();
+            let last = table.shown_mut().last().unwrap();
+            match (last.width > excess, shown) {
+                (true, _) | (false, 1) => last.width = last.width.saturating_sub(excess),
+                (false, _) => last.hidden = true,
+            }
+        }
+
+        let left = width.saturating_sub(table.width());
+        let wide = table.shown().filter(|column| column.wide).count();
+        if let Some(share) = left.checked_div(wide) {
+            let wide_columns = table.shown_mut().filter(|column| column.wide);
+            for (index, column) in wide_columns.enumerate() {
+                // The first columns get the columns that can't be shared evenly.
+                column.width += share + usize::from(index < left % wide);
+            }
+        }
+        table
+    }
+
+    /// The columns not left out for lack of room.
+    fn shown(&self) -> impl DoubleEndedIterator<Item = &Column> {
+        self.columns.iter().filter(|column| !column.hidden)
+    }
+
+    fn shown_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Column> {
+        self.columns.iter_mut().filter(|column| !column.hidden)
+    }
+
+    /// How many columns of the terminal a row takes.
+    fn width(&self) -> usize {
+        let shown = self.shown().count();
+        self.shown().map(|column| column.width).sum::<usize>()
+            + SEPARATOR.len() * shown.saturating_sub(1)
+    }
+
+    /// The titles, centered in their columns.
+    pub fn header(&self) -> String {
+        let titles = self
+            .shown()
+            .map(|column| {
+                if column.title.width() > column.width {
+                    return fit(&column.title, column.width);
+                }
+                let space = column.width - column.title.width();
+                let left = space / 2;
+                format!(
+                    "{}{}{}",
+                    " ".repeat(left),
+                    column.title,
+                    " ".repeat(space - left)
+                )
+            })
+            .collect::<Vec<_>>();
+        self.join(titles)
+    }
+
+    /// A line under the header, for tables without other lines.
+    pub fn rule(&self) -> String {
+        let rules = self.shown().map(|column| "-".repeat(column.width));
+        rules.collect::<Vec<_>>().join("-+-")
+    }
+
+    /// Each text fit to its column. Missing cells are left empty, the texts of columns
+    /// left out are skipped.
+    pub fn cells(&self, texts: &[&str]) -> Vec<String> {
+        self.columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| !column.hidden)
+            .map(|(index, column)| fit(texts.get(index).copied().unwrap_or(""), column.width))
+            .collect()
+    }
+
+    /// Cells, as given by [`Table::cells`] and maybe highlighted, joined into a row.
+    pub fn join(&self, cells: Vec<String>) -> String {
+        cells.join(SEPARATOR)
+    }
+
+    pub fn row(&self, texts: &[&str]) -> String {
+        self.join(self.cells(texts))
+    }
+}
+
+/// `text` padded to `width` columns of the terminal, or cut to them with an ellipsis.
+/// Characters made of several code points, like flags or accents, are never split.
+pub fn fit(text: &str, width: usize) -> String {
+    let text_width = text.width();
+    if text_width <= width {
+        return format!("{text}{}", " ".repeat(width - text_width));
+    }
+    if width <= ELLIPSIS.len() {
+        return ".".repeat(width);
+    }
+
+    let mut fitted = String::new();
+    let mut fitted_width = 0;
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        if fitted_width + grapheme_width > width - ELLIPSIS.len() {
+            break;
+        }
+        fitted.push_str(grapheme);
+        fitted_width += grapheme_width;
+    }
+    // A wide character that didn't fit leaves a column to pad.
+    let padding = width - ELLIPSIS.len() - fitted_width;
+    format!("{fitted}{ELLIPSIS}{}", " ".repeat(padding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_should_pad_and_cut_to_the_width() {
+        let text = "testmetest";
+
+        assert_eq!(fit(text, 0), "");
+        assert_eq!(fit(text, 1), ".");
+        assert_eq!(fit(text, 2), "..");
+        assert_eq!(fit(text, 3), "...");
+        assert_eq!(fit(text, 4), "t...");
+
+        assert_eq!(fit("", 6), "      ");
+        assert_eq!(fit("test", 6), "test  ");
+        assert_eq!(fit("testme", 6), "testme");
+        assert_eq!(fit(text, 6), "tes...");
+    }
+
+    #[test]
+    fn fit_should_measure_what_the_terminal_shows() {
+        assert_eq!(fit("Café", 6), "Café  ");
+        assert_eq!(fit("Cafe\u{301}", 6), "Cafe\u{301}  ");
+        assert_eq!(fit("Cafe\u{301} au lait", 7), "Cafe\u{301}...");
+        assert_eq!(fit("日本語", 6), "日本語");
+        assert_eq!(fit("日本語のテキスト", 8), "日本... ");
+        assert_eq!(fit("🚀 launch", 10), "🚀 launch ");
+        for text in [
+            "日本語のテキスト",
+            "🚀🚀🚀🚀🚀",
+            "🇯🇵🇫🇷🇧🇷🇩🇪",
+            "Cafe\u{301} au lait",
+        ] {
+            for width in 0..12 {
+                assert_eq!(fit(text, width).width(), width, "{text} in {width}");
+            }
+        }
+    }
+
+    #[test]
+    fn table_should_share_the_terminal_between_wide_columns() {
+        let columns = || {
+            [
+                Column::fixed("id", 4),
+                Column::wide("name", 6),
+                Column::wide("labels", 6),
+            ]
+        };
+
+        let narrow = Table::with_width(columns(), 22);
+        assert_eq!(narrow.header(), " id  |  name  | labels");
+        assert_eq!(narrow.row(&["1", "Checkout"]), "1    | Che... |       ");
+        assert_eq!(narrow.rule(), "-----+--------+-------");
+
+        let wide = Table::with_width(columns(), 29);
+        assert_eq!(wide.row(&["1", "Checkout", "ünï"]).width(), 29);
+        assert_eq!(
+            wide.row(&["1", "Checkout", "ünï"]),
+            "1    | Checkout   | ünï      "
+        );
+    }
+
+    #[test]
+    fn table_should_fit_narrow_terminals() {
+        let columns = || {
+            [
+                Column::fixed("id", 11),
+                Column::wide("name", 18),
+                Column::fixed("owner", 10),
+                Column::wide("labels", 14).optional(),
+                Column::fixed("due", 12).optional(),
+                Column::fixed("priority", 9),
+                Column::fixed("status", 13),
+            ]
+        };
+        let texts = [
+            "PAY-1",
+            "Checkout",
+            "alice",
+            "billing",
+            "2024-05-01",
+            "High",
+            "In Progress",
+        ];
+
+        // Wide columns narrow first, then the optional columns go from the right.
+        let table = Table::with_width(columns(), 80);
+        assert_eq!(table.header().width(), 80);
+        assert!(table.header().contains("labels"));
+        assert!(!table.header().contains("due"));
+        assert_eq!(table.row(&texts).width(), 80);
+
+        let table = Table::with_width(columns(), 64);
+        assert!(!table.header().contains("labels"));
+        assert!(table.row(&texts).contains("In Progress"));
+
+        for width in 0..120 {
+            let table = Table::with_width(columns(), width);
+            assert!(table.header().width() <= width, "header in {width}");
+            assert!(table.rule().width() <= width, "rule in {width}");
+            assert!(table.row(&texts).width() <= width, "row in {width}");
+        }
+        assert_eq!(Table::with_width(columns(), 8).header(), "   id   ");
+        assert_eq!(Table::with_width(columns(), 1).header(), ".");
+    }
+}
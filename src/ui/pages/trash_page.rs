@@ -0,0 +1,168 @@
+use anyhow::Result;
+use itertools::Itertools;
+use std::rc::Rc;
+
+use crate::db::JiraDatabase;
+use crate::models::Action;
+
+use super::{
+    table::{Column, Table},
+    Page,
+};
+
+/// Deleted epics and stories, which can be restored or purged for good.
+pub struct TrashBin {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for TrashBin {
+    fn draw_page(&self) -> Result<()> {
+        let db_state = self.db.read()?;
+        let trash = &db_state.trash;
+        let keys = self.db.key_prefix();
+
+        let table = Table::new([
+            Column::fixed("id", 11),
+            Column::wide("name", 32),
+            Column::fixed("deleted", 17),
+        ]);
+
+        println!("------------------------- DELETED EPICS -------------------------");
+        println!("{}", table.header());
+        trash.epics.keys().sorted().for_each(|epic_id| {
+            let trashed = &trash.epics[epic_id];
+            let deleted_at = trashed.deleted_at.format("%Y-%m-%d %H:%M").to_string();
+            println!(
+                "{}",
+                table.row(&[&keys.key(*epic_id), &trashed.epic.name, &deleted_at])
+            );
+        });
+
+        println!();
+
+        println!("------------------------ DELETED STORIES ------------------------");
+        println!("{}", table.header());
+        trash.stories.keys().sorted().for_each(|story_id| {
+            let trashed = &trash.stories[story_id];
+            let deleted_at = trashed.deleted_at.format("%Y-%m-%d %H:%M").to_string();
+            println!(
+                "{}",
+                table.row(&[&keys.key(*story_id), &trashed.story.name, &deleted_at])
+            );
+        });
+
+        println!();
+        println!();
+
+        println!(
+            "[p] previous | [r :id:] restore | [x :id:] delete permanently | [z] undo | [Z] redo"
+        );
+
+        Ok(())
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "z" => Ok(Some(Action::Undo)),
+            "Z" => Ok(Some(Action::Redo)),
+            input => {
+                let mut chars = input.chars();
+                let command = chars.next();
+                let Some(id) = self.db.key_prefix().parse(chars.as_str().trim_start()) else {
+                    return Ok(None);
+                };
+
+                let trash = self.db.snapshot()?.trash;
+                let action = match command {
+                    Some('r') if trash.epics.contains_key(&id) => {
+                        Action::RestoreEpic { epic_id: id }
+                    }
+                    Some('r') if trash.stories.contains_key(&id) => {
+                        Action::RestoreStory { story_id: id }
+                    }
+                    Some('x') if trash.epics.contains_key(&id) => Action::PurgeEpic { epic_id: id },
+                    Some('x') if trash.stories.contains_key(&id) => {
+                        Action::PurgeStory { story_id: id }
+                    }
+                    _ => return Ok(None),
+                };
+                Ok(Some(action))
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_utils::MockDB,
+        models::{Epic, Story},
+    };
+
+    fn build_page() -> (TrashBin, u32, u32) {
+        let database = Box::new(MockDB::new());
+        let db = Rc::new(JiraDatabase::from_database(database));
+
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.delete_story(epic_id, story_id).unwrap();
+        db.delete_epic(epic_id).unwrap();
+
+        (TrashBin { db }, epic_id, story_id)
+    }
+
+    #[test]
+    fn draw_page_should_not_throw_error() {
+        let (page, _, _) = build_page();
+        assert!(page.draw_page().is_ok());
+    }
+
+    #[test]
+    fn handle_input_should_not_throw_on_invalid_input() {
+        let (page, _, _) = build_page();
+
+        assert!(page.handle_input("").unwrap().is_none());
+        assert!(page.handle_input("j983f2j").unwrap().is_none());
+        assert!(page.handle_input("r999").unwrap().is_none());
+        assert!(page.handle_input("é1").unwrap().is_none());
+        assert!(page.handle_input("p\n").unwrap().is_none());
+    }
+
+    #[test]
+    fn handle_input_should_return_correct_action() {
+        let (page, epic_id, story_id) = build_page();
+
+        assert_eq!(
+            page.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(
+            page.handle_input(&format!("r {epic_id}")).unwrap(),
+            Some(Action::RestoreEpic { epic_id })
+        );
+        assert_eq!(
+            page.handle_input(&format!("r{story_id}")).unwrap(),
+            Some(Action::RestoreStory { story_id })
+        );
+        assert_eq!(
+            page.handle_input(&format!("x {epic_id}")).unwrap(),
+            Some(Action::PurgeEpic { epic_id })
+        );
+        assert_eq!(
+            page.handle_input(&format!("x {story_id}")).unwrap(),
+            Some(Action::PurgeStory { story_id })
+        );
+        assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+        assert_eq!(page.handle_input("Z").unwrap(), Some(Action::Redo));
+    }
+}
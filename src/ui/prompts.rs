@@ -1,12 +1,40 @@
-use crate::io_utils::get_user_input;
-use crate::models::{Epic, Status, Story};
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use itertools::Itertools;
+
+use crate::io_utils::{edit_in_editor, get_user_input, today, DESCRIPTION_HEADER};
+use crate::models::{parse_due_date, Epic, KeyPrefix, Priority, Status, Story, User};
+
+const EDITOR_HINT: &str = ":e to write it in your editor";
+
+type PickUser = dyn Fn(&BTreeMap<String, User>) -> Option<String>;
+type PickLabel = dyn Fn(&BTreeSet<String>) -> Option<String>;
+type PickEpic = dyn Fn(&KeyPrefix, &BTreeMap<u32, String>) -> Option<u32>;
+type EditText = dyn Fn(&str) -> Option<String>;
+type WriteComment = dyn Fn(Option<&str>) -> Option<String>;
+type PickComment = dyn Fn(&[u32]) -> Option<u32>;
 
 pub struct Prompts {
     pub create_epic: Box<dyn Fn() -> Epic>,
     pub create_story: Box<dyn Fn() -> Story>,
     pub delete_epic: Box<dyn Fn() -> bool>,
     pub delete_story: Box<dyn Fn() -> bool>,
+    pub purge_item: Box<dyn Fn() -> bool>,
+    /// Gets the current value, `None` keeps it.
+    pub update_name: Box<EditText>,
+    pub update_description: Box<EditText>,
     pub update_status: Box<dyn Fn() -> Option<Status>>,
+    pub update_priority: Box<dyn Fn() -> Option<Priority>>,
+    pub pick_user: Box<PickUser>,
+    pub add_label: Box<dyn Fn() -> Option<String>>,
+    pub pick_label: Box<PickLabel>,
+    pub update_due_date: Box<dyn Fn() -> Option<NaiveDate>>,
+    /// Gets the names of the epics to pick from.
+    pub pick_epic: Box<PickEpic>,
+    /// Gets the text of the comment being edited, if any.
+    pub write_comment: Box<WriteComment>,
+    pub pick_comment: Box<PickComment>,
 }
 
 impl Prompts {
@@ -16,7 +44,18 @@ impl Prompts {
             create_story: Box::new(create_story_prompt),
             delete_epic: Box::new(delete_epic_prompt),
             delete_story: Box::new(delete_story_prompt),
+            purge_item: Box::new(purge_item_prompt),
+            update_name: Box::new(update_name_prompt),
+            update_description: Box::new(update_description_prompt),
             update_status: Box::new(update_status_prompt),
+            update_priority: Box::new(update_priority_prompt),
+            pick_user: Box::new(pick_user_prompt),
+            add_label: Box::new(add_label_prompt),
+            pick_label: Box::new(pick_label_prompt),
+            update_due_date: Box::new(update_due_date_prompt),
+            pick_epic: Box::new(pick_epic_prompt),
+            write_comment: Box::new(write_comment_prompt),
+            pick_comment: Box::new(pick_comment_prompt),
         }
     }
 }
@@ -26,10 +65,11 @@ fn create_epic_prompt() -> Epic {
     println!("Epic Name:");
     let name = get_user_input();
 
-    println!("Epic Description:");
-    let description = get_user_input();
+    let description = read_description("Epic Description", "Describe the new epic");
 
-    Epic::new(name.trim().to_string(), description.trim().to_string())
+    let mut epic = Epic::new(name.trim().to_string(), description);
+    epic.priority = read_priority("Epic Priority").unwrap_or_default();
+    epic
 }
 
 fn create_story_prompt() -> Story {
@@ -37,15 +77,16 @@ fn create_story_prompt() -> Story {
     println!("Story Name:");
     let name = get_user_input();
 
-    println!("Story Description:");
-    let description = get_user_input();
+    let description = read_description("Story Description", "Describe the new story");
 
-    Story::new(name.trim().to_string(), description.trim().to_string())
+    let mut story = Story::new(name.trim().to_string(), description);
+    story.priority = read_priority("Story Priority").unwrap_or_default();
+    story
 }
 
 fn delete_epic_prompt() -> bool {
     println!("----------------------------");
-    println!("Are you sure you want to delete this epic? All stories in this epic will also be deleted [Y/n]:");
+    println!("Are you sure you want to delete this epic? It will be moved to the trash together with all its stories [Y/n]:");
     let answer = get_user_input();
 
     answer.trim().to_lowercase().eq("y")
@@ -53,12 +94,45 @@ fn delete_epic_prompt() -> bool {
 
 fn delete_story_prompt() -> bool {
     println!("----------------------------");
-    println!("Are you sure you want to delete this story? [Y/n]:");
+    println!("Are you sure you want to delete this story? It will be moved to the trash [Y/n]:");
+    let answer = get_user_input();
+
+    answer.trim().to_lowercase().eq("y")
+}
+
+fn purge_item_prompt() -> bool {
+    println!("----------------------------");
+    println!("Are you sure you want to permanently delete this item? It can't be restored [Y/n]:");
     let answer = get_user_input();
 
     answer.trim().to_lowercase().eq("y")
 }
 
+fn update_name_prompt(current: &str) -> Option<String> {
+    println!("----------------------------");
+    println!("Current Name: {current}");
+    println!("New Name (empty to keep it):");
+    let answer = get_user_input();
+    let answer = answer.trim();
+
+    (!answer.is_empty()).then(|| answer.to_owned())
+}
+
+/// Like the name, `-` clears the description and `:e` edits it in the editor.
+fn update_description_prompt(current: &str) -> Option<String> {
+    println!("----------------------------");
+    println!("Current Description:");
+    println!("{current}");
+    println!("New Description (empty to keep it, - to clear it, {EDITOR_HINT}):");
+    let answer = get_user_input();
+    match answer.trim() {
+        "" => None,
+        "-" => Some(String::new()),
+        ":e" => edit_description(DESCRIPTION_HEADER, current),
+        answer => Some(answer.to_owned()),
+    }
+}
+
 fn update_status_prompt() -> Option<Status> {
     println!("----------------------------");
     println!("New Status (1 - OPEN, 2 - IN-PROGRESS, 3 - RESOLVED, 4 - CLOSED):");
@@ -71,3 +145,135 @@ fn update_status_prompt() -> Option<Status> {
         _ => None,
     }
 }
+
+fn update_priority_prompt() -> Option<Priority> {
+    println!("----------------------------");
+    read_priority("New Priority")
+}
+
+/// Takes the number or the username of one of `users`.
+fn pick_user_prompt(users: &BTreeMap<String, User>) -> Option<String> {
+    println!("----------------------------");
+    for (index, (username, user)) in users.iter().enumerate() {
+        match user.name.as_str() {
+            "" => println!("{} - {username}", index + 1),
+            name => println!("{} - {username} ({name})", index + 1),
+        }
+    }
+    println!("User:");
+    let answer = get_user_input();
+    let answer = answer.trim();
+
+    let picked = match answer.parse::<usize>() {
+        Ok(number) => users.keys().nth(number.checked_sub(1)?),
+        Err(_) => users.keys().find(|username| username.as_str() == answer),
+    };
+    picked.cloned()
+}
+
+fn add_label_prompt() -> Option<String> {
+    println!("----------------------------");
+    println!("Label:");
+    let answer = get_user_input();
+    let answer = answer.trim();
+
+    (!answer.is_empty()).then(|| answer.to_owned())
+}
+
+/// Takes the number or the name of one of `labels`, `None` when nothing is picked.
+fn pick_label_prompt(labels: &BTreeSet<String>) -> Option<String> {
+    println!("----------------------------");
+    for (index, label) in labels.iter().enumerate() {
+        println!("{} - {label}", index + 1);
+    }
+    println!("Label (empty for none):");
+    let answer = get_user_input();
+    let answer = answer.trim();
+
+    let picked = match answer.parse::<usize>() {
+        Ok(number) => labels.iter().nth(number.checked_sub(1)?),
+        Err(_) => labels.get(answer),
+    };
+    picked.cloned()
+}
+
+/// Asks again until a valid date or nothing is entered.
+fn update_due_date_prompt() -> Option<NaiveDate> {
+    println!("----------------------------");
+    loop {
+        println!("Due Date (2023-05-19, +3d, +2w, next friday, empty to cancel):");
+        let answer = get_user_input();
+        if answer.trim().is_empty() {
+            return None;
+        }
+        match parse_due_date(&answer, today()) {
+            Ok(date) => return Some(date),
+            Err(err) => println!("{err}"),
+        }
+    }
+}
+
+/// Takes the key or id of one of `epics`.
+fn pick_epic_prompt(keys: &KeyPrefix, epics: &BTreeMap<u32, String>) -> Option<u32> {
+    println!("----------------------------");
+    for (epic_id, name) in epics {
+        println!("{} - {name}", keys.key(*epic_id));
+    }
+    println!("Epic:");
+    let epic_id = keys.parse(get_user_input().trim())?;
+
+    epics.contains_key(&epic_id).then_some(epic_id)
+}
+
+fn write_comment_prompt(current: Option<&str>) -> Option<String> {
+    println!("----------------------------");
+    if let Some(current) = current {
+        println!("Current Comment: {current}");
+    }
+    println!("Comment (empty to cancel):");
+    let answer = get_user_input();
+    let answer = answer.trim();
+
+    (!answer.is_empty()).then(|| answer.to_owned())
+}
+
+/// Takes one of `comment_ids`, as shown in the thread (`3` or `#3`).
+fn pick_comment_prompt(comment_ids: &[u32]) -> Option<u32> {
+    println!("----------------------------");
+    let ids = comment_ids.iter().map(|id| format!("#{id}")).join(", ");
+    println!("Comment ({ids}):");
+    let answer = get_user_input();
+    let id = answer.trim().trim_start_matches('#').parse::<u32>().ok()?;
+
+    comment_ids.contains(&id).then_some(id)
+}
+
+/// A single line, or `:e` to write several in the editor.
+fn read_description(label: &str, header: &str) -> String {
+    println!("{label} ({EDITOR_HINT}):");
+    let answer = get_user_input();
+    match answer.trim() {
+        ":e" => {
+            edit_description(&format!("{header}.\n{DESCRIPTION_HEADER}"), "").unwrap_or_default()
+        }
+        answer => answer.to_owned(),
+    }
+}
+
+/// `None` when the editor can't be started, after saying why.
+fn edit_description(header: &str, text: &str) -> Option<String> {
+    match edit_in_editor(header, text) {
+        Ok(description) => Some(description),
+        Err(err) => {
+            println!("{err:#}");
+            None
+        }
+    }
+}
+
+/// `None` unless one of the listed numbers is entered.
+fn read_priority(label: &str) -> Option<Priority> {
+    println!("{label} (1 - HIGHEST, 2 - HIGH, 3 - MEDIUM, 4 - LOW, 5 - LOWEST):");
+    let answer = get_user_input().trim().parse::<usize>().ok()?;
+    Priority::ALL.get(answer.checked_sub(1)?).copied()
+}
@@ -0,0 +1,239 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, ConflictError, JiraDatabase};
+use crate::models::DBState;
+
+/// Each entry holds two full copies of the board, so only the most recent ones are kept.
+const MAX_ENTRIES: usize = 20;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct UndoEntry {
+    pub label: String,
+    pub before: DBState,
+    pub after: DBState,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct UndoStacks {
+    undo: Vec<UndoEntry>,
+    redo: Vec<UndoEntry>,
+}
+
+/// Undo/redo stacks of whole-board snapshots, optionally persisted to a file so they
+/// survive a restart.
+///
+/// An entry on the undo stack can only be applied while the board is still at
+/// `after.revision`, and one on the redo stack while it is at `before.revision`, so we
+/// never roll back a teammate's change.
+pub struct UndoHistory {
+    stacks: UndoStacks,
+    file_path: Option<String>,
+}
+
+impl UndoHistory {
+    pub fn in_memory() -> Self {
+        Self {
+            stacks: UndoStacks::default(),
+            file_path: None,
+        }
+    }
+
+    pub fn load(file_path: String) -> Result<Self> {
+        let stacks = if Path::new(&file_path).exists() {
+            let contents = fs::read(&file_path)
+                .with_context(|| format!("Failed to read undo history {file_path}"))?;
+            serde_json::from_slice(&contents)
+                .with_context(|| format!("Failed to parse undo history {file_path}"))?
+        } else {
+            UndoStacks::default()
+        };
+
+        Ok(Self {
+            stacks,
+            file_path: Some(file_path),
+        })
+    }
+
+    pub fn record(&mut self, label: String, before: DBState, after: DBState) -> Result<()> {
+        self.stacks.undo.push(UndoEntry {
+            label,
+            before,
+            after,
+        });
+        if self.stacks.undo.len() > MAX_ENTRIES {
+            self.stacks.undo.remove(0);
+        }
+        self.stacks.redo.clear();
+        self.save()
+    }
+
+    /// Rolls back the latest change, returning its label, or `None` if there is nothing
+    /// to undo.
+    pub fn undo(&mut self, db: &JiraDatabase) -> Result<Option<String>> {
+        let Some(entry) = self.stacks.undo.pop() else {
+            return Ok(None);
+        };
+
+        let restored = match db.restore(entry.before, entry.after.revision) {
+            Ok(restored) => restored,
+            Err(err) => return self.discard_on_conflict(err),
+        };
+        // Restoring bumps the revision, the entry below now starts from the restored state.
+        if let Some(previous) = self.stacks.undo.last_mut() {
+            previous.after = restored.clone();
+        }
+        self.stacks.redo.push(UndoEntry {
+            label: entry.label.clone(),
+            before: restored,
+            after: entry.after,
+        });
+        self.save()?;
+        Ok(Some(entry.label))
+    }
+
+    /// Re-applies the latest undone change, returning its label, or `None` if there is
+    /// nothing to redo.
+    pub fn redo(&mut self, db: &JiraDatabase) -> Result<Option<String>> {
+        let Some(entry) = self.stacks.redo.pop() else {
+            return Ok(None);
+        };
+
+        let reapplied = match db.restore(entry.after, entry.before.revision) {
+            Ok(reapplied) => reapplied,
+            Err(err) => return self.discard_on_conflict(err),
+        };
+        if let Some(next) = self.stacks.redo.last_mut() {
+            next.before = reapplied.clone();
+        }
+        self.stacks.undo.push(UndoEntry {
+            label: entry.label.clone(),
+            before: entry.before,
+            after: reapplied,
+        });
+        self.save()?;
+        Ok(Some(entry.label))
+    }
+
+    /// Once someone else changed the board none of the entries can be applied anymore.
+    fn discard_on_conflict(&mut self, err: anyhow::Error) -> Result<Option<String>> {
+        if err.downcast_ref::<ConflictError>().is_none() {
+            return Err(err);
+        }
+
+        self.stacks = UndoStacks::default();
+        self.save()?;
+        Err(anyhow!(
+            "The board was changed by someone else, the undo history was discarded"
+        ))
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(file_path) = &self.file_path else {
+            return Ok(());
+        };
+
+        let contents = serde_json::to_vec(&self.stacks)?;
+        db::write_atomically(Path::new(file_path), &contents)
+            .with_context(|| format!("Failed to write undo history {file_path}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_utils::MockDB,
+        models::{Epic, Status},
+    };
+
+    fn record_last_write(history: &mut UndoHistory, db: &JiraDatabase, label: &str) {
+        let (before, after) = db.take_last_write().unwrap();
+        history.record(label.to_owned(), before, after).unwrap();
+    }
+
+    #[test]
+    fn undo_and_redo_should_work() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let mut history = UndoHistory::in_memory();
+
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        record_last_write(&mut history, &db, "CreateEpic");
+        db.update_epic_status(epic_id, Status::Closed).unwrap();
+        record_last_write(&mut history, &db, "UpdateEpicStatus");
+
+        assert_eq!(
+            history.undo(&db).unwrap(),
+            Some("UpdateEpicStatus".to_owned())
+        );
+        assert_eq!(db.read().unwrap().epics[&epic_id].status, Status::Open);
+
+        assert_eq!(history.undo(&db).unwrap(), Some("CreateEpic".to_owned()));
+        let db_state = db.read().unwrap();
+        assert!(db_state.epics.is_empty());
+        assert_eq!(db_state.last_item_id, 1);
+        assert_eq!(history.undo(&db).unwrap(), None);
+
+        assert_eq!(history.redo(&db).unwrap(), Some("CreateEpic".to_owned()));
+        assert_eq!(
+            history.redo(&db).unwrap(),
+            Some("UpdateEpicStatus".to_owned())
+        );
+        assert_eq!(db.read().unwrap().epics[&epic_id].status, Status::Closed);
+        assert_eq!(history.redo(&db).unwrap(), None);
+    }
+
+    #[test]
+    fn record_should_clear_redo_stack() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let mut history = UndoHistory::in_memory();
+
+        db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        record_last_write(&mut history, &db, "CreateEpic");
+        history.undo(&db).unwrap();
+
+        db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        record_last_write(&mut history, &db, "CreateEpic");
+
+        assert_eq!(history.redo(&db).unwrap(), None);
+    }
+
+    #[test]
+    fn undo_should_not_overwrite_someone_elses_change() {
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+        let mut history = UndoHistory::in_memory();
+
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        record_last_write(&mut history, &db, "CreateEpic");
+        db.update_epic_status(epic_id, Status::Closed).unwrap();
+
+        assert!(history.undo(&db).is_err());
+        assert!(db.read().unwrap().epics.contains_key(&epic_id));
+        assert_eq!(history.undo(&db).unwrap(), None);
+    }
+
+    #[test]
+    fn history_should_persist_across_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("db.json.undo.json");
+        let file_path = file_path.to_str().unwrap().to_string();
+        let db = JiraDatabase::from_database(Box::new(MockDB::new()));
+
+        let mut history = UndoHistory::load(file_path.clone()).unwrap();
+        db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        record_last_write(&mut history, &db, "CreateEpic");
+
+        let mut history = UndoHistory::load(file_path).unwrap();
+        assert_eq!(history.undo(&db).unwrap(), Some("CreateEpic".to_owned()));
+        assert!(db.read().unwrap().epics.is_empty());
+    }
+}